@@ -1,6 +1,5 @@
 use bevy::{
     prelude::{MeshMaterial2d, *},
-    render::render_resource::LoadOp,
     window::PrimaryWindow,
 };
 use bevy_egui::{
@@ -84,18 +83,18 @@ fn setup_system(
         .spawn((
             Mesh2d(meshes.add(Rectangle::new(256.0, 256.0))),
             MeshMaterial2d(materials.add(mesh_image_handle.clone())),
-            EguiRenderToImage {
-                handle: mesh_image_handle,
-                load_op: LoadOp::Clear(Color::srgb_u8(43, 44, 47).to_linear().into()),
-            },
+            EguiRenderToImage::with_clear_color(
+                mesh_image_handle,
+                Color::srgb_u8(43, 44, 47).to_linear(),
+            ),
         ))
         .id();
 
     app_state.egui_texture_image_entity = commands
-        .spawn(EguiRenderToImage {
-            handle: egui_texture_image_handle.clone(),
-            load_op: LoadOp::Clear(Color::srgb_u8(43, 44, 47).to_linear().into()),
-        })
+        .spawn(EguiRenderToImage::with_clear_color(
+            egui_texture_image_handle.clone(),
+            Color::srgb_u8(43, 44, 47).to_linear(),
+        ))
         .id();
     app_state.egui_texture_image_handle = egui_texture_image_handle.clone_weak();
     app_state.egui_texture_image_id =
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContextReadQuery, EguiContexts, EguiPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin)
+        .add_systems(Update, inspector_system)
+        .run();
+}
+
+// `EguiContextReadQuery` doesn't borrow `EguiContext`, so it can be used together with `EguiContexts`.
+fn inspector_system(mut contexts: EguiContexts, context_states: Query<EguiContextReadQuery>) {
+    egui::Window::new("Contexts").show(contexts.ctx_mut(), |ui| {
+        egui::Grid::new("contexts").striped(true).show(ui, |ui| {
+            ui.strong("Entity");
+            ui.strong("Size");
+            ui.strong("Pointer");
+            ui.strong("Wants pointer");
+            ui.strong("Wants keyboard");
+            ui.strong("Paint jobs");
+            ui.strong("Vertices");
+            ui.end_row();
+
+            for context in context_states.iter() {
+                ui.label(format!("{:?}", context.entity));
+                ui.label(format!(
+                    "{}x{}",
+                    context.render_target_size.width(),
+                    context.render_target_size.height()
+                ));
+                ui.label(format!(
+                    "{:.1}, {:.1}",
+                    context.pointer_position.position.x, context.pointer_position.position.y
+                ));
+                ui.label(context.wants_input.pointer.to_string());
+                ui.label(context.wants_input.keyboard.to_string());
+                ui.label(context.render_output.paint_jobs.len().to_string());
+                ui.label(context.tessellation_stats.vertices.to_string());
+                ui.end_row();
+            }
+        });
+    });
+}
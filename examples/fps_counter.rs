@@ -0,0 +1,55 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    render::camera::RenderTarget,
+    window::{WindowRef, WindowResolution},
+};
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(FrameTimeDiagnosticsPlugin)
+        .add_plugins(EguiPlugin)
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, fps_counter_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    let second_window_id = commands
+        .spawn(Window {
+            title: "Second window".to_owned(),
+            resolution: WindowResolution::new(800.0, 600.0),
+            ..Default::default()
+        })
+        .id();
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(second_window_id)),
+            ..Default::default()
+        },
+    ));
+}
+
+fn fps_counter_system(mut contexts: EguiContexts, diagnostics: Res<DiagnosticsStore>) {
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed());
+
+    contexts.for_each_context(|entity, is_primary, ctx| {
+        egui::Area::new(egui::Id::new("fps_counter"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+            .show(ctx, |ui| {
+                let window = if is_primary { "primary" } else { "secondary" };
+                ui.label(format!("{window} window ({entity})"));
+                match fps {
+                    Some(fps) => ui.label(format!("FPS: {fps:.0}")),
+                    None => ui.label("FPS: -"),
+                };
+            });
+    });
+}
@@ -1,4 +1,4 @@
-use bevy::{prelude::*, render::render_resource::LoadOp, window::PrimaryWindow};
+use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_egui::{EguiContexts, EguiPlugin, EguiRenderToImage};
 use wgpu_types::{Extent3d, TextureUsages};
 
@@ -110,10 +110,7 @@ fn setup_worldspace_system(
                 unlit: true,
                 ..default()
             })),
-            EguiRenderToImage {
-                handle: image,
-                load_op: LoadOp::Clear(Color::srgb_u8(43, 44, 47).to_linear().into()),
-            },
+            EguiRenderToImage::with_clear_color(image, Color::srgb_u8(43, 44, 47).to_linear()),
             // We want the "tablet" mesh behind to react to pointer inputs.
             PickingBehavior {
                 should_block_lower: false,
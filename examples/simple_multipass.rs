@@ -2,7 +2,8 @@ use std::num::NonZero;
 
 use bevy::prelude::*;
 use bevy_egui::{
-    EguiContext, EguiContextSettings, EguiFullOutput, EguiInput, EguiPlugin, EguiStartupSet,
+    EguiContext, EguiContextSettings, EguiFullOutput, EguiInput, EguiManualPass, EguiPlugin,
+    EguiStartupSet,
 };
 
 fn main() {
@@ -13,7 +14,7 @@ fn main() {
             PreStartup,
             configure_context.after(EguiStartupSet::InitContexts),
         )
-        .add_systems(Update, ui_example_system)
+        .add_systems(Update, ui_example_system.in_set(EguiManualPass))
         .run();
 }
 
@@ -0,0 +1,128 @@
+use bevy_log as log;
+use jni::{
+    objects::{JObject, JString, JValue},
+    JNIEnv, JavaVM,
+};
+
+/// Internal implementation of [`crate::EguiClipboard`] for Android.
+///
+/// Is backed by the `android.content.ClipboardManager` system service, which is accessed via JNI.
+#[derive(Default)]
+pub struct AndroidClipboard;
+
+impl AndroidClipboard {
+    /// Places the text onto the clipboard.
+    pub fn set_text(&mut self, text: &str) {
+        if let Err(err) = with_clipboard_manager(|env, clipboard_manager| {
+            let label = env.new_string("bevy_egui")?;
+            let text = env.new_string(text)?;
+            let clip_data = env
+                .call_static_method(
+                    "android/content/ClipData",
+                    "newPlainText",
+                    "(Ljava/lang/CharSequence;Ljava/lang/CharSequence;)Landroid/content/ClipData;",
+                    &[JValue::Object(&label), JValue::Object(&text)],
+                )?
+                .l()?;
+            env.call_method(
+                clipboard_manager,
+                "setPrimaryClip",
+                "(Landroid/content/ClipData;)V",
+                &[JValue::Object(&clip_data)],
+            )?;
+            Ok(())
+        }) {
+            log::error!("Failed to set clipboard contents: {:?}", err);
+        }
+    }
+
+    /// Gets clipboard text content. Returns an empty string if the clipboard is empty or contains no text,
+    /// returns [`None`] if the clipboard manager is unavailable.
+    pub fn get_text(&mut self) -> Option<String> {
+        let result = with_clipboard_manager(|env, clipboard_manager| {
+            let clip_data = env
+                .call_method(
+                    clipboard_manager,
+                    "getPrimaryClip",
+                    "()Landroid/content/ClipData;",
+                    &[],
+                )?
+                .l()?;
+            if clip_data.is_null() {
+                return Ok(String::new());
+            }
+            let item_count = env
+                .call_method(&clip_data, "getItemCount", "()I", &[])?
+                .i()?;
+            if item_count <= 0 {
+                return Ok(String::new());
+            }
+
+            let item = env
+                .call_method(
+                    &clip_data,
+                    "getItemAt",
+                    "(I)Landroid/content/ClipData$Item;",
+                    &[JValue::Int(0)],
+                )?
+                .l()?;
+            let char_sequence = env
+                .call_method(&item, "getText", "()Ljava/lang/CharSequence;", &[])?
+                .l()?;
+            if char_sequence.is_null() {
+                return Ok(String::new());
+            }
+
+            let string = JString::from(
+                env.call_method(&char_sequence, "toString", "()Ljava/lang/String;", &[])?
+                    .l()?,
+            );
+            let contents: String = env.get_string(&string)?.into();
+            Ok(contents)
+        });
+
+        match result {
+            Ok(contents) => Some(contents),
+            Err(err) => {
+                log::error!("Failed to get clipboard contents: {:?}", err);
+                None
+            }
+        }
+    }
+
+    /// Places an image to the clipboard.
+    ///
+    /// Isn't supported on Android, as `ClipboardManager` works with content URIs for images.
+    pub fn set_image(&mut self, _image: &egui::ColorImage) {
+        log::warn!("Copying images to the clipboard isn't supported on Android");
+    }
+}
+
+fn with_clipboard_manager<T>(
+    f: impl FnOnce(&mut JNIEnv, &JObject) -> jni::errors::Result<T>,
+) -> jni::errors::Result<T> {
+    let android_context = ndk_context::android_context();
+    // SAFETY: the pointer is provided by the `android-activity` glue and stays valid for the lifetime of the app.
+    let vm = unsafe { JavaVM::from_raw(android_context.vm().cast()) }?;
+    let mut env = vm.attach_current_thread()?;
+    // SAFETY: the context is a global reference that is owned by the activity, we never delete it.
+    let context = unsafe { JObject::from_raw(android_context.context().cast()) };
+
+    let result = env.with_local_frame(16, |env| {
+        let service_name = env.new_string("clipboard")?;
+        let clipboard_manager = env
+            .call_method(
+                &context,
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+                &[JValue::Object(&service_name)],
+            )?
+            .l()?;
+        f(env, &clipboard_manager)
+    });
+    if result.is_err() {
+        // A pending Java exception would abort the next JNI call made from this thread.
+        let _ = env.exception_clear();
+    }
+    result
+}
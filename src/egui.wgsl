@@ -1,6 +1,19 @@
 struct Transform {
     scale: vec2<f32>,
     translation: vec2<f32>,
+    color_matrix: mat4x4<f32>,
+}
+
+// Matches `TextureColorConversion`.
+const COLOR_CONVERSION_YUV444: u32 = 1u;
+const COLOR_CONVERSION_YUV420: u32 = 2u;
+const COLOR_CONVERSION_PREMULTIPLIED: u32 = 3u;
+
+struct TextureParams {
+    color_conversion: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
 }
 
 struct VertexInput {
@@ -18,6 +31,7 @@ struct VertexOutput {
 @group(0) @binding(0) var<uniform> transform: Transform;
 @group(1) @binding(0) var image_texture: texture_2d<f32>;
 @group(1) @binding(1) var image_sampler: sampler;
+@group(1) @binding(2) var<uniform> texture_params: TextureParams;
 
 fn linear_from_srgb(srgb: vec3<f32>) -> vec3<f32> {
     let cutoff = srgb < vec3<f32>(0.04045);
@@ -26,6 +40,74 @@ fn linear_from_srgb(srgb: vec3<f32>) -> vec3<f32> {
     return select(higher, lower, cutoff);
 }
 
+fn srgb_from_linear(linear: vec3<f32>) -> vec3<f32> {
+    let cutoff = linear < vec3<f32>(0.0031308);
+    let lower = linear * 12.92;
+    let higher = 1.055 * pow(max(linear, vec3<f32>(0.0)), vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(higher, lower, cutoff);
+}
+
+// BT.709 limited range YUV to gamma-encoded RGB.
+fn rgb_from_yuv(yuv: vec3<f32>) -> vec3<f32> {
+    let y = (yuv.x - 16.0 / 255.0) * (255.0 / 219.0);
+    let u = (yuv.y - 128.0 / 255.0) * (255.0 / 224.0);
+    let v = (yuv.z - 128.0 / 255.0) * (255.0 / 224.0);
+    let rgb = vec3<f32>(y + 1.5748 * v, y - 0.1873 * u - 0.4681 * v, y + 1.8556 * u);
+    return clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Reads a texel of an I420 frame: the Y plane is followed by the U and V planes of a quarter size each.
+fn load_yuv420(uv: vec2<f32>) -> vec3<f32> {
+    let texture_size = textureDimensions(image_texture);
+    let width = texture_size.x;
+    let height = texture_size.y * 2u / 3u;
+    let frame_size = vec2<f32>(f32(width), f32(height));
+    let position = min(vec2<u32>(clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0)) * frame_size), vec2<u32>(width - 1u, height - 1u));
+    let chroma_index = (position.y / 2u) * (width / 2u) + position.x / 2u;
+    let u_index = width * height + chroma_index;
+    let v_index = u_index + width * height / 4u;
+    let y = textureLoad(image_texture, position, 0).r;
+    let u = textureLoad(image_texture, vec2<u32>(u_index % width, u_index / width), 0).r;
+    let v = textureLoad(image_texture, vec2<u32>(v_index % width, v_index / width), 0).r;
+    return vec3<f32>(y, u, v);
+}
+
+fn sample_texture(uv: vec2<f32>) -> vec4<f32> {
+    switch texture_params.color_conversion {
+        case COLOR_CONVERSION_YUV444: {
+            let yuv = textureSample(image_texture, image_sampler, uv).rgb;
+            return vec4<f32>(linear_from_srgb(rgb_from_yuv(yuv)), 1.0);
+        }
+        case COLOR_CONVERSION_YUV420: {
+            return vec4<f32>(linear_from_srgb(rgb_from_yuv(load_yuv420(uv))), 1.0);
+        }
+        case COLOR_CONVERSION_PREMULTIPLIED: {
+            // Unmultiplied here, as colors get premultiplied in the fragment shader.
+            let color = textureSample(image_texture, image_sampler, uv);
+            return vec4<f32>(select(color.rgb / color.a, vec3<f32>(0.0), color.a == 0.0), color.a);
+        }
+        default: {
+            return textureSample(image_texture, image_sampler, uv);
+        }
+    }
+}
+
+#ifdef DITHERING
+// Interleaved gradient noise by Jorge Jimenez, see
+// https://www.iryoku.com/next-generation-post-processing-in-call-of-duty-advanced-warfare/.
+fn interleaved_gradient_noise(n: vec2<f32>) -> f32 {
+    let f = 0.06711056 * n.x + 0.00583715 * n.y;
+    return fract(52.9829189 * fract(f));
+}
+
+fn dither(linear: vec3<f32>, frag_coord: vec2<f32>) -> vec3<f32> {
+    // Dithering is applied in the gamma space to match the 8-bit quantization steps of sRGB targets.
+    let noise = (interleaved_gradient_noise(frag_coord) - 0.5) * 0.95;
+    let srgb = srgb_from_linear(linear) + noise / 255.0;
+    return linear_from_srgb(clamp(srgb, vec3<f32>(0.0), vec3<f32>(1.0)));
+}
+#endif
+
 @vertex
 fn vs_main(in: VertexInput) -> VertexOutput {
     let position = in.position * transform.scale + transform.translation;
@@ -35,9 +117,17 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    let texture_color = textureSample(image_texture, image_sampler, in.uv);
+    let texture_color = sample_texture(in.uv);
     // This assumes that texture images are not premultiplied.
     let color = in.color * vec4<f32>(texture_color.rgb * texture_color.a, texture_color.a);
 
-    return color;
+    var output = transform.color_matrix * color;
+#ifdef DITHERING
+    output = vec4<f32>(dither(output.rgb, in.position.xy), output.a);
+#endif
+#ifdef GAMMA_OUTPUT
+    // The target isn't sRGB-encoded, so the encoding needs to happen in the shader.
+    output = vec4<f32>(srgb_from_linear(output.rgb), output.a);
+#endif
+    return output;
 }
@@ -1,9 +1,10 @@
 use crate::{
     render_systems::{
-        EguiPipelines, EguiRenderData, EguiTextureBindGroups, EguiTextureId, EguiTransform,
-        EguiTransforms,
+        EguiBlendStatePipelines, EguiPipelines, EguiRenderData, EguiRenderTargetData,
+        EguiTextureBindGroups, EguiTextureId, EguiTransform, EguiTransforms,
     },
-    EguiRenderToImage,
+    EguiContextSettings, EguiLayer, EguiLayerRect, EguiPassThrottle, EguiRenderToImage,
+    EguiRenderToTextureView, RenderTargetSize, TextureColorConversion,
 };
 use bevy_asset::prelude::*;
 use bevy_ecs::{
@@ -12,17 +13,19 @@ use bevy_ecs::{
 };
 use bevy_image::{Image, ImageAddressMode, ImageFilterMode, ImageSampler, ImageSamplerDescriptor};
 use bevy_render::{
+    camera::{ManualTextureView, ManualTextureViews},
     render_asset::{RenderAssetUsages, RenderAssets},
     render_graph::{Node, NodeRunError, RenderGraphContext},
     render_phase::TrackedRenderPass,
     render_resource::{
-        BindGroupLayout, BindGroupLayoutEntry, BindingType, BlendComponent, BlendFactor,
-        BlendOperation, BlendState, BufferBindingType, ColorTargetState, ColorWrites,
-        CommandEncoderDescriptor, Extent3d, FragmentState, FrontFace, IndexFormat, LoadOp,
-        MultisampleState, Operations, PipelineCache, PrimitiveState, RenderPassColorAttachment,
-        RenderPassDescriptor, RenderPipelineDescriptor, SamplerBindingType, Shader, ShaderStages,
-        ShaderType, SpecializedRenderPipeline, StoreOp, TextureDimension, TextureFormat,
-        TextureSampleType, TextureViewDimension, VertexBufferLayout, VertexFormat, VertexState,
+        BindGroup, BindGroupLayout, BindGroupLayoutEntry, BindingType, BlendState, Buffer,
+        BufferBindingType, BufferInitDescriptor, BufferSize, BufferUsages, CachedRenderPipelineId,
+        ColorTargetState, ColorWrites, CommandEncoder, CommandEncoderDescriptor, Extent3d,
+        FragmentState, FrontFace, IndexFormat, LoadOp, MultisampleState, Operations, PipelineCache,
+        PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline,
+        RenderPipelineDescriptor, SamplerBindingType, Shader, ShaderStages, ShaderType,
+        SpecializedRenderPipeline, StoreOp, TextureDimension, TextureFormat, TextureSampleType,
+        TextureView, TextureViewDimension, VertexBufferLayout, VertexFormat, VertexState,
         VertexStepMode,
     },
     renderer::{RenderContext, RenderDevice},
@@ -30,7 +33,12 @@ use bevy_render::{
     texture::GpuImage,
     view::{ExtractedWindow, ExtractedWindows},
 };
+use bevy_utils::HashMap;
 use egui::{TextureFilter, TextureOptions};
+use std::sync::atomic::Ordering;
+
+/// Size of the texture parameters uniform (see [`EguiPipeline::color_conversion_buffer`]).
+const TEXTURE_PARAMS_SIZE: u64 = 16;
 
 /// Egui shader.
 pub const EGUI_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(9898276442290979394);
@@ -42,6 +50,16 @@ pub struct EguiPipeline {
     pub transform_bind_group_layout: BindGroupLayout,
     /// Texture bind group layout.
     pub texture_bind_group_layout: BindGroupLayout,
+    /// Uniform buffers with texture parameters, indexed by [`TextureColorConversion`].
+    color_conversion_buffers: Vec<Buffer>,
+}
+
+impl EguiPipeline {
+    /// Returns a uniform buffer with texture parameters for the texture bind group
+    /// (binding 2 of [`EguiPipeline::texture_bind_group_layout`]).
+    pub fn color_conversion_buffer(&self, color_conversion: TextureColorConversion) -> &Buffer {
+        &self.color_conversion_buffers[color_conversion as usize]
+    }
 }
 
 impl FromWorld for EguiPipeline {
@@ -52,7 +70,7 @@ impl FromWorld for EguiPipeline {
             "egui transform bind group layout",
             &[BindGroupLayoutEntry {
                 binding: 0,
-                visibility: ShaderStages::VERTEX,
+                visibility: ShaderStages::VERTEX_FRAGMENT,
                 ty: BindingType::Buffer {
                     ty: BufferBindingType::Uniform,
                     has_dynamic_offset: true,
@@ -81,23 +99,59 @@ impl FromWorld for EguiPipeline {
                     ty: BindingType::Sampler(SamplerBindingType::Filtering),
                     count: None,
                 },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(TEXTURE_PARAMS_SIZE),
+                    },
+                    count: None,
+                },
             ],
         );
 
+        let color_conversion_buffers = [
+            TextureColorConversion::Rgba,
+            TextureColorConversion::Yuv444,
+            TextureColorConversion::Yuv420,
+            TextureColorConversion::Premultiplied,
+        ]
+        .into_iter()
+        .map(|color_conversion| {
+            // The struct is padded to 16 bytes to satisfy uniform buffer layout requirements of WebGL.
+            let texture_params: [u32; 4] = [color_conversion as u32, 0, 0, 0];
+            render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("egui texture params buffer"),
+                contents: bytemuck::cast_slice(&texture_params),
+                usage: BufferUsages::UNIFORM,
+            })
+        })
+        .collect();
+
         EguiPipeline {
             transform_bind_group_layout,
             texture_bind_group_layout,
+            color_conversion_buffers,
         }
     }
 }
 
 /// Key for specialized pipeline.
+///
+/// The output color conversion depends on [`EguiPipelineKey::texture_format`]: colors are written as linear
+/// for sRGB and float (HDR) formats, and gamma-encoded for other formats.
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub struct EguiPipelineKey {
     /// Texture format of a window's swap chain to render to.
     pub texture_format: TextureFormat,
     /// Render target type (e.g. window, image).
     pub render_target_type: EguiRenderTargetType,
+    /// Blend state of the color target (see [`EguiContextSettings::blend_state`]).
+    pub blend_state: BlendState,
+    /// Whether the output gets dithered (see [`EguiContextSettings::dithering`]).
+    pub dithering: bool,
 }
 
 /// Is used to make a render node aware of a render target type.
@@ -107,22 +161,52 @@ pub enum EguiRenderTargetType {
     Window,
     /// Render to an image.
     Image,
+    /// Render to a manual texture view.
+    TextureView,
 }
 
 impl EguiPipelineKey {
     /// Constructs a pipeline key from a window.
-    pub fn from_extracted_window(window: &ExtractedWindow) -> Option<Self> {
+    pub fn from_extracted_window(
+        window: &ExtractedWindow,
+        egui_settings: &EguiContextSettings,
+    ) -> Option<Self> {
         Some(Self {
             texture_format: window.swap_chain_texture_format?.add_srgb_suffix(),
             render_target_type: EguiRenderTargetType::Window,
+            blend_state: egui_settings
+                .blend_state
+                .unwrap_or(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            dithering: egui_settings.dithering,
         })
     }
 
+    /// Constructs a pipeline key from a manual texture view.
+    ///
+    /// Unlike images, the format of the view is used as is, as the view can't be reinterpreted.
+    pub fn from_manual_texture_view(
+        texture_view: &ManualTextureView,
+        egui_settings: &EguiContextSettings,
+    ) -> Self {
+        EguiPipelineKey {
+            texture_format: texture_view.format,
+            render_target_type: EguiRenderTargetType::TextureView,
+            blend_state: egui_settings
+                .blend_state
+                .unwrap_or(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            dithering: egui_settings.dithering,
+        }
+    }
+
     /// Constructs a pipeline key from a gpu image.
-    pub fn from_gpu_image(image: &GpuImage) -> Self {
+    pub fn from_gpu_image(image: &GpuImage, egui_settings: &EguiContextSettings) -> Self {
         EguiPipelineKey {
             texture_format: image.texture_format.add_srgb_suffix(),
             render_target_type: EguiRenderTargetType::Image,
+            blend_state: egui_settings
+                .blend_state
+                .unwrap_or(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            dithering: egui_settings.dithering,
         }
     }
 }
@@ -131,6 +215,26 @@ impl SpecializedRenderPipeline for EguiPipeline {
     type Key = EguiPipelineKey;
 
     fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        // Float (HDR) targets store linear values as is and don't suffer from banding,
+        // 8-bit targets without an sRGB view expect gamma-encoded values.
+        let is_float_target = matches!(
+            key.texture_format,
+            TextureFormat::R16Float
+                | TextureFormat::Rg16Float
+                | TextureFormat::Rgba16Float
+                | TextureFormat::R32Float
+                | TextureFormat::Rg32Float
+                | TextureFormat::Rgba32Float
+                | TextureFormat::Rg11b10Ufloat
+        );
+        let mut fragment_shader_defs = Vec::new();
+        if key.dithering && !is_float_target {
+            fragment_shader_defs.push("DITHERING".into());
+        }
+        if !is_float_target && !key.texture_format.is_srgb() {
+            fragment_shader_defs.push("GAMMA_OUTPUT".into());
+        }
+
         RenderPipelineDescriptor {
             label: Some("egui render pipeline".into()),
             layout: vec![
@@ -152,22 +256,11 @@ impl SpecializedRenderPipeline for EguiPipeline {
             },
             fragment: Some(FragmentState {
                 shader: EGUI_SHADER_HANDLE,
-                shader_defs: Vec::new(),
+                shader_defs: fragment_shader_defs,
                 entry_point: "fs_main".into(),
                 targets: vec![Some(ColorTargetState {
                     format: key.texture_format,
-                    blend: Some(BlendState {
-                        color: BlendComponent {
-                            src_factor: BlendFactor::One,
-                            dst_factor: BlendFactor::OneMinusSrcAlpha,
-                            operation: BlendOperation::Add,
-                        },
-                        alpha: BlendComponent {
-                            src_factor: BlendFactor::One,
-                            dst_factor: BlendFactor::OneMinusSrcAlpha,
-                            operation: BlendOperation::Add,
-                        },
-                    }),
+                    blend: Some(key.blend_state),
                     write_mask: ColorWrites::ALL,
                 })],
             }),
@@ -192,8 +285,13 @@ pub(crate) struct DrawCommand {
 pub(crate) enum DrawPrimitive {
     Egui(EguiDraw),
     PaintCallback(PaintCallbackDraw),
+    /// Switches the blend state of the following primitives, [`None`] restores the default one.
+    SetBlendState(Option<BlendState>),
 }
 
+/// Paint callback payload that marks the start or the end of a layer listed in [`crate::EguiLayerBlendStates`].
+pub(crate) struct EguiBlendStateMarker(pub(crate) Option<BlendState>);
+
 pub(crate) struct PaintCallbackDraw {
     pub(crate) callback: std::sync::Arc<EguiBevyPaintCallback>,
     pub(crate) rect: egui::Rect,
@@ -228,32 +326,68 @@ impl EguiNode {
 
 impl Node for EguiNode {
     fn update(&mut self, world: &mut World) {
-        world.resource_scope(|world, mut render_data: Mut<EguiRenderData>| {
-            let Some(data) = render_data.0.get_mut(&self.render_target_main_entity) else {
-                return;
-            };
+        update_paint_callbacks(
+            world,
+            self.render_target_main_entity,
+            self.render_target_render_entity,
+        );
+    }
 
-            let (Some(render_target_size), Some(key)) = (data.render_target_size, data.key) else {
-                bevy_log::warn!("Failed to retrieve egui node data!");
-                return;
-            };
+    fn run<'w>(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        let Some(pass) = PreparedEguiPass::new(
+            render_context,
+            world,
+            self.render_target_main_entity,
+            self.render_target_render_entity,
+            self.render_target_type,
+        ) else {
+            return Ok(());
+        };
 
-            for (clip_rect, command) in data.postponed_updates.drain(..) {
-                let info = egui::PaintCallbackInfo {
-                    viewport: command.rect,
-                    clip_rect,
-                    pixels_per_point: data.pixels_per_point,
-                    screen_size_px: [
-                        render_target_size.physical_width as u32,
-                        render_target_size.physical_height as u32,
-                    ],
-                };
-                command
-                    .callback
-                    .cb()
-                    .update(info, self.render_target_render_entity, key, world);
-            }
+        render_context.add_command_buffer_generation_task(move |device| {
+            let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("egui_node_command_encoder"),
+            });
+            pass.record(&device, &mut command_encoder);
+            command_encoder.finish()
         });
+
+        Ok(())
+    }
+}
+
+/// Render-to-image contexts drawn by [`EguiRenderToImageBatchNode`] instead of having their own [`EguiNode`]
+/// (see [`crate::EguiGlobalSettings::batch_render_to_image_nodes`]).
+#[derive(Resource, Default)]
+pub struct EguiRenderToImageBatch(pub HashMap<MainEntity, RenderEntity>);
+
+/// Egui render node that draws all the contexts listed in [`EguiRenderToImageBatch`].
+///
+/// The passes of the contexts are recorded one after another into a single command encoder.
+#[derive(Default)]
+pub struct EguiRenderToImageBatchNode {
+    contexts: Vec<(MainEntity, RenderEntity)>,
+}
+
+impl Node for EguiRenderToImageBatchNode {
+    fn update(&mut self, world: &mut World) {
+        self.contexts.clear();
+        self.contexts.extend(
+            world
+                .resource::<EguiRenderToImageBatch>()
+                .0
+                .iter()
+                .map(|(main_entity, render_entity)| (*main_entity, *render_entity)),
+        );
+
+        for &(main_entity, render_entity) in &self.contexts {
+            update_paint_callbacks(world, main_entity, render_entity);
+        }
     }
 
     fn run<'w>(
@@ -262,72 +396,224 @@ impl Node for EguiNode {
         render_context: &mut RenderContext<'w>,
         world: &'w World,
     ) -> Result<(), NodeRunError> {
+        let passes: Vec<_> = self
+            .contexts
+            .iter()
+            .filter_map(|&(main_entity, render_entity)| {
+                PreparedEguiPass::new(
+                    render_context,
+                    world,
+                    main_entity,
+                    render_entity,
+                    EguiRenderTargetType::Image,
+                )
+            })
+            .collect();
+        if passes.is_empty() {
+            return Ok(());
+        }
+
+        render_context.add_command_buffer_generation_task(move |device| {
+            let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("egui_render_to_image_batch_command_encoder"),
+            });
+            for pass in passes {
+                pass.record(&device, &mut command_encoder);
+            }
+            command_encoder.finish()
+        });
+
+        Ok(())
+    }
+}
+
+/// Runs the [`EguiBevyPaintCallbackImpl::update`] step of paint callbacks of a render target.
+fn update_paint_callbacks(
+    world: &mut World,
+    render_target_main_entity: MainEntity,
+    render_target_render_entity: RenderEntity,
+) {
+    world.resource_scope(|world, mut render_data: Mut<EguiRenderData>| {
+        let Some(data) = render_data.0.get_mut(&render_target_main_entity) else {
+            return;
+        };
+
+        let (Some(render_target_size), Some(key)) = (data.render_target_size, data.key) else {
+            bevy_log::warn!("Failed to retrieve egui node data!");
+            return;
+        };
+
+        for (clip_rect, command) in data.postponed_updates.drain(..) {
+            let info = egui::PaintCallbackInfo {
+                viewport: command.rect,
+                clip_rect,
+                pixels_per_point: data.pixels_per_point,
+                screen_size_px: [
+                    render_target_size.physical_width as u32,
+                    render_target_size.physical_height as u32,
+                ],
+            };
+            command
+                .callback
+                .cb()
+                .update(info, render_target_render_entity, key, world);
+        }
+    });
+}
+
+/// Everything needed to record the render pass of a single render target.
+struct PreparedEguiPass<'w> {
+    world: &'w World,
+    data: &'w EguiRenderTargetData,
+    render_target_main_entity: MainEntity,
+    render_target_render_entity: RenderEntity,
+    key: EguiPipelineKey,
+    texture_view: &'w TextureView,
+    load_op: LoadOp<wgpu_types::Color>,
+    viewport_offset: bevy_math::UVec2,
+    physical_width: u32,
+    physical_height: u32,
+    render_target_urect: bevy_math::URect,
+    vertex_buffer: &'w Buffer,
+    index_buffer: &'w Buffer,
+    pipeline: &'w RenderPipeline,
+    pipeline_cache: &'w PipelineCache,
+    blend_state_pipelines: &'w HashMap<(MainEntity, BlendState), CachedRenderPipelineId>,
+    bind_groups: &'w EguiTextureBindGroups,
+    transform_buffer_offset: u32,
+    transform_buffer_bind_group: &'w BindGroup,
+}
+
+impl<'w> PreparedEguiPass<'w> {
+    /// Collects the render target data and runs the [`EguiBevyPaintCallbackImpl::prepare_render`] step
+    /// of paint callbacks, returns [`None`] if there's nothing to draw.
+    fn new(
+        render_context: &mut RenderContext<'w>,
+        world: &'w World,
+        render_target_main_entity: MainEntity,
+        render_target_render_entity: RenderEntity,
+        render_target_type: EguiRenderTargetType,
+    ) -> Option<Self> {
         let egui_pipelines = &world.resource::<EguiPipelines>().0;
         let pipeline_cache = world.resource::<PipelineCache>();
         let render_data = world.resource::<EguiRenderData>();
 
-        let Some(data) = render_data.0.get(&self.render_target_main_entity) else {
+        let Some(data) = render_data.0.get(&render_target_main_entity) else {
             bevy_log::warn!("Failed to retrieve render data for egui node rendering!");
-            return Ok(());
+            return None;
         };
 
-        let (key, swap_chain_texture_view, physical_width, physical_height, load_op) =
-            match self.render_target_type {
-                EguiRenderTargetType::Window => {
-                    let Some(window) = world
-                        .resource::<ExtractedWindows>()
-                        .windows
-                        .get(&self.render_target_main_entity.id())
-                    else {
-                        return Ok(());
-                    };
+        if world
+            .get::<EguiPassThrottle>(render_target_render_entity.id())
+            .is_some_and(|throttle| throttle.skip_pass)
+        {
+            return None;
+        }
 
-                    let Some(swap_chain_texture_view) = &window.swap_chain_texture_view else {
-                        return Ok(());
-                    };
+        let egui_settings = world.get::<EguiContextSettings>(render_target_render_entity.id())?;
+        let (key, texture_view, physical_width, physical_height, load_op) = match render_target_type
+        {
+            EguiRenderTargetType::Window => {
+                // Layers are drawn on top of the window they belong to.
+                let layer = world.get::<EguiLayer>(render_target_render_entity.id());
+                let window_entity =
+                    layer.map_or(render_target_main_entity.id(), |layer| layer.window);
+                let window = world
+                    .resource::<ExtractedWindows>()
+                    .windows
+                    .get(&window_entity)?;
+                let swap_chain_texture_view = window.swap_chain_texture_view.as_ref()?;
+                let key = EguiPipelineKey::from_extracted_window(window, egui_settings)?;
+                (
+                    key,
+                    swap_chain_texture_view,
+                    window.physical_width,
+                    window.physical_height,
+                    if layer.is_some() {
+                        LoadOp::Load
+                    } else {
+                        egui_settings.window_load_op
+                    },
+                )
+            }
+            EguiRenderTargetType::Image => {
+                let extracted_render_to_image: &EguiRenderToImage =
+                    world.get(render_target_render_entity.id())?;
+
+                let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+                let gpu_image = gpu_images.get(&extracted_render_to_image.handle)?;
+                (
+                    EguiPipelineKey::from_gpu_image(gpu_image, egui_settings),
+                    &gpu_image.texture_view,
+                    gpu_image.size.x,
+                    gpu_image.size.y,
+                    data.load_op_override
+                        .unwrap_or(extracted_render_to_image.load_op),
+                )
+            }
+            EguiRenderTargetType::TextureView => {
+                let extracted_render_to_texture_view: &EguiRenderToTextureView =
+                    world.get(render_target_render_entity.id())?;
+
+                let manual_texture_views = world.resource::<ManualTextureViews>();
+                let texture_view =
+                    manual_texture_views.get(&extracted_render_to_texture_view.handle)?;
+                (
+                    EguiPipelineKey::from_manual_texture_view(texture_view, egui_settings),
+                    &texture_view.texture_view,
+                    texture_view.size.x,
+                    texture_view.size.y,
+                    data.load_op_override
+                        .unwrap_or(extracted_render_to_texture_view.load_op),
+                )
+            }
+        };
 
-                    let Some(key) = EguiPipelineKey::from_extracted_window(window) else {
-                        return Ok(());
-                    };
-                    (
-                        key,
-                        swap_chain_texture_view,
-                        window.physical_width,
-                        window.physical_height,
-                        LoadOp::Load,
-                    )
-                }
-                EguiRenderTargetType::Image => {
-                    let Some(extracted_render_to_image): Option<&EguiRenderToImage> =
-                        world.get(self.render_target_render_entity.id())
-                    else {
-                        return Ok(());
-                    };
+        // Nothing to draw into zero-sized targets (e.g. minimized windows).
+        if physical_width == 0 || physical_height == 0 {
+            return None;
+        }
 
-                    let gpu_images = world.resource::<RenderAssets<GpuImage>>();
-                    let Some(gpu_image) = gpu_images.get(&extracted_render_to_image.handle) else {
-                        return Ok(());
-                    };
-                    (
-                        EguiPipelineKey::from_gpu_image(gpu_image),
-                        &gpu_image.texture_view,
-                        gpu_image.size.x,
-                        gpu_image.size.y,
-                        extracted_render_to_image.load_op,
-                    )
+        // Layers restricted to a rect are drawn into the corresponding part of the window, everything below
+        // operates in the coordinates of the rect, which get offset when setting the viewport and scissors.
+        let viewport_urect = match world.get::<EguiLayerRect>(render_target_render_entity.id()) {
+            Some(EguiLayerRect(rect)) if render_target_type == EguiRenderTargetType::Window => {
+                let scale_factor = world
+                    .get::<RenderTargetSize>(render_target_render_entity.id())
+                    .map_or(1.0, |size| size.scale_factor);
+                bevy_math::URect {
+                    min: (rect.min * scale_factor)
+                        .round()
+                        .max(bevy_math::Vec2::ZERO)
+                        .as_uvec2(),
+                    max: (rect.max * scale_factor)
+                        .round()
+                        .max(bevy_math::Vec2::ZERO)
+                        .as_uvec2(),
                 }
-            };
-
-        let (vertex_buffer, index_buffer) = match (&data.vertex_buffer, &data.index_buffer) {
-            (Some(vertex), Some(index)) => (vertex, index),
-            _ => {
-                return Ok(());
+                .intersect(bevy_math::URect::new(
+                    0,
+                    0,
+                    physical_width,
+                    physical_height,
+                ))
             }
+            _ => bevy_math::URect::new(0, 0, physical_width, physical_height),
+        };
+        if viewport_urect.is_empty() {
+            return None;
+        }
+        let viewport_offset = viewport_urect.min;
+        let (physical_width, physical_height) = (viewport_urect.width(), viewport_urect.height());
+
+        let (Some(vertex_buffer), Some(index_buffer)) = (&data.vertex_buffer, &data.index_buffer)
+        else {
+            return None;
         };
 
         for draw_command in &data.draw_commands {
             match &draw_command.primitive {
-                DrawPrimitive::Egui(_command) => {}
+                DrawPrimitive::Egui(_) | DrawPrimitive::SetBlendState(_) => {}
                 DrawPrimitive::PaintCallback(command) => {
                     let info = egui::PaintCallbackInfo {
                         viewport: command.rect,
@@ -339,7 +625,7 @@ impl Node for EguiNode {
                     command.callback.cb().prepare_render(
                         info,
                         render_context,
-                        self.render_target_render_entity,
+                        render_target_render_entity,
                         key,
                         world,
                     );
@@ -348,165 +634,227 @@ impl Node for EguiNode {
         }
 
         let pipeline_id = egui_pipelines
-            .get(&self.render_target_main_entity)
+            .get(&render_target_main_entity)
             .expect("Expected a queued pipeline");
-        let Some(pipeline) = pipeline_cache.get_render_pipeline(*pipeline_id) else {
-            return Ok(());
-        };
-
-        let bind_groups = world.resource::<EguiTextureBindGroups>();
-        let egui_transforms = world.resource::<EguiTransforms>();
-        let transform_buffer_offset = egui_transforms.offsets[&self.render_target_main_entity];
-        let transform_buffer_bind_group = &egui_transforms
-            .bind_group
-            .as_ref()
-            .expect("Expected a prepared bind group")
-            .1;
-        let render_target_render_entity = self.render_target_render_entity;
-
-        render_context.add_command_buffer_generation_task(move |device| {
-            let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
-                label: Some("egui_node_command_encoder"),
-            });
-
-            let render_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
-                label: Some("egui render pass"),
-                color_attachments: &[Some(RenderPassColorAttachment {
-                    view: swap_chain_texture_view,
-                    resolve_target: None,
-                    ops: Operations {
-                        load: load_op,
-                        store: StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-            let mut render_pass = TrackedRenderPass::new(&device, render_pass);
-
-            let mut requires_reset = true;
-            let mut last_scissor_rect = None;
-
-            let mut vertex_offset: u32 = 0;
-            for draw_command in &data.draw_commands {
-                if requires_reset {
-                    render_pass.set_viewport(
-                        0.,
-                        0.,
-                        physical_width as f32,
-                        physical_height as f32,
-                        0.,
-                        1.,
-                    );
-                    last_scissor_rect = None;
-                    render_pass.set_render_pipeline(pipeline);
-                    render_pass.set_bind_group(
-                        0,
-                        transform_buffer_bind_group,
-                        &[transform_buffer_offset],
-                    );
-
-                    requires_reset = false;
-                }
-
-                let clip_urect = bevy_math::URect {
+        let pipeline = pipeline_cache.get_render_pipeline(*pipeline_id)?;
+        let render_target_urect = data.dirty_rect.map_or(
+            bevy_math::URect::new(0, 0, physical_width, physical_height),
+            |dirty_rect| {
+                bevy_math::URect {
                     min: bevy_math::UVec2 {
-                        x: (draw_command.clip_rect.min.x * data.pixels_per_point).round() as u32,
-                        y: (draw_command.clip_rect.min.y * data.pixels_per_point).round() as u32,
+                        x: (dirty_rect.min.x * data.pixels_per_point).floor().max(0.0) as u32,
+                        y: (dirty_rect.min.y * data.pixels_per_point).floor().max(0.0) as u32,
                     },
                     max: bevy_math::UVec2 {
-                        x: (draw_command.clip_rect.max.x * data.pixels_per_point).round() as u32,
-                        y: (draw_command.clip_rect.max.y * data.pixels_per_point).round() as u32,
+                        x: (dirty_rect.max.x * data.pixels_per_point).ceil().max(0.0) as u32,
+                        y: (dirty_rect.max.y * data.pixels_per_point).ceil().max(0.0) as u32,
                     },
-                };
-
-                let scissor_rect = clip_urect.intersect(bevy_math::URect::new(
+                }
+                .intersect(bevy_math::URect::new(
                     0,
                     0,
                     physical_width,
                     physical_height,
-                ));
-                if scissor_rect.is_empty() {
-                    continue;
+                ))
+            },
+        );
+
+        let egui_transforms = world.resource::<EguiTransforms>();
+        Some(Self {
+            world,
+            data,
+            render_target_main_entity,
+            render_target_render_entity,
+            key,
+            texture_view,
+            load_op,
+            viewport_offset,
+            physical_width,
+            physical_height,
+            render_target_urect,
+            vertex_buffer,
+            index_buffer,
+            pipeline,
+            pipeline_cache,
+            blend_state_pipelines: &world.resource::<EguiBlendStatePipelines>().0,
+            bind_groups: world.resource::<EguiTextureBindGroups>(),
+            transform_buffer_offset: egui_transforms.offsets[&render_target_main_entity],
+            transform_buffer_bind_group: &egui_transforms
+                .bind_group
+                .as_ref()
+                .expect("Expected a prepared bind group")
+                .1,
+        })
+    }
+
+    /// Records the render pass into a command encoder.
+    fn record(self, device: &RenderDevice, command_encoder: &mut CommandEncoder) {
+        let Self {
+            world,
+            data,
+            render_target_main_entity,
+            render_target_render_entity,
+            key,
+            texture_view,
+            load_op,
+            viewport_offset,
+            physical_width,
+            physical_height,
+            render_target_urect,
+            vertex_buffer,
+            index_buffer,
+            pipeline,
+            pipeline_cache,
+            blend_state_pipelines,
+            bind_groups,
+            transform_buffer_offset,
+            transform_buffer_bind_group,
+        } = self;
+
+        let render_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("egui render pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: texture_view,
+                resolve_target: None,
+                ops: Operations {
+                    load: load_op,
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let mut render_pass = TrackedRenderPass::new(device, render_pass);
+
+        let mut requires_reset = true;
+        let mut last_scissor_rect = None;
+        let mut current_pipeline = pipeline;
+
+        let mut vertex_offset: u32 = 0;
+        for draw_command in &data.draw_commands {
+            if let DrawPrimitive::SetBlendState(blend_state) = &draw_command.primitive {
+                // Falling back to the default pipeline if a blend state pipeline isn't compiled yet.
+                current_pipeline = blend_state
+                    .and_then(|blend_state| {
+                        blend_state_pipelines.get(&(render_target_main_entity, blend_state))
+                    })
+                    .and_then(|pipeline_id| pipeline_cache.get_render_pipeline(*pipeline_id))
+                    .unwrap_or(pipeline);
+                requires_reset = true;
+                continue;
+            }
+
+            if requires_reset {
+                render_pass.set_viewport(
+                    viewport_offset.x as f32,
+                    viewport_offset.y as f32,
+                    physical_width as f32,
+                    physical_height as f32,
+                    0.,
+                    1.,
+                );
+                last_scissor_rect = None;
+                render_pass.set_render_pipeline(current_pipeline);
+                render_pass.set_bind_group(
+                    0,
+                    transform_buffer_bind_group,
+                    &[transform_buffer_offset],
+                );
+
+                requires_reset = false;
+            }
+
+            let clip_urect = bevy_math::URect {
+                min: bevy_math::UVec2 {
+                    x: (draw_command.clip_rect.min.x * data.pixels_per_point).round() as u32,
+                    y: (draw_command.clip_rect.min.y * data.pixels_per_point).round() as u32,
+                },
+                max: bevy_math::UVec2 {
+                    x: (draw_command.clip_rect.max.x * data.pixels_per_point).round() as u32,
+                    y: (draw_command.clip_rect.max.y * data.pixels_per_point).round() as u32,
+                },
+            };
+
+            // Only the dirty region is drawn in case of a partial redraw.
+            let scissor_rect = clip_urect.intersect(render_target_urect);
+            if scissor_rect.is_empty() {
+                if let DrawPrimitive::Egui(command) = &draw_command.primitive {
+                    vertex_offset += command.vertices_count as u32;
                 }
+                continue;
+            }
 
-                if Some(scissor_rect) != last_scissor_rect {
-                    last_scissor_rect = Some(scissor_rect);
+            if Some(scissor_rect) != last_scissor_rect {
+                last_scissor_rect = Some(scissor_rect);
+
+                // Bevy TrackedRenderPass doesn't track set_scissor_rect calls
+                // So set_scissor_rect is updated only when it is needed
+                render_pass.set_scissor_rect(
+                    viewport_offset.x + scissor_rect.min.x,
+                    viewport_offset.y + scissor_rect.min.y,
+                    scissor_rect.width(),
+                    scissor_rect.height(),
+                );
+            }
+
+            match &draw_command.primitive {
+                DrawPrimitive::Egui(command) => {
+                    let texture_bind_group = match bind_groups.get(&command.egui_texture) {
+                        Some(texture_resource) => texture_resource,
+                        None => {
+                            vertex_offset += command.vertices_count as u32;
+                            continue;
+                        }
+                    };
+
+                    render_pass.set_bind_group(1, texture_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                    render_pass.set_index_buffer(index_buffer.slice(..), 0, IndexFormat::Uint32);
 
-                    // Bevy TrackedRenderPass doesn't track set_scissor_rect calls
-                    // So set_scissor_rect is updated only when it is needed
-                    render_pass.set_scissor_rect(
-                        scissor_rect.min.x,
-                        scissor_rect.min.y,
-                        scissor_rect.width(),
-                        scissor_rect.height(),
+                    render_pass.draw_indexed(
+                        vertex_offset..(vertex_offset + command.vertices_count as u32),
+                        0,
+                        0..1,
                     );
+
+                    vertex_offset += command.vertices_count as u32;
                 }
+                DrawPrimitive::PaintCallback(command) => {
+                    let info = egui::PaintCallbackInfo {
+                        viewport: command.rect,
+                        clip_rect: draw_command.clip_rect,
+                        pixels_per_point: data.pixels_per_point,
+                        screen_size_px: [physical_width, physical_height],
+                    };
 
-                match &draw_command.primitive {
-                    DrawPrimitive::Egui(command) => {
-                        let texture_bind_group = match bind_groups.get(&command.egui_texture) {
-                            Some(texture_resource) => texture_resource,
-                            None => {
-                                vertex_offset += command.vertices_count as u32;
-                                continue;
-                            }
-                        };
-
-                        render_pass.set_bind_group(1, texture_bind_group, &[]);
-                        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-                        render_pass.set_index_buffer(
-                            index_buffer.slice(..),
-                            0,
-                            IndexFormat::Uint32,
+                    let viewport = info.viewport_in_pixels();
+                    if viewport.width_px > 0 && viewport.height_px > 0 {
+                        requires_reset = true;
+                        render_pass.set_viewport(
+                            (viewport_offset.x as i32 + viewport.left_px) as f32,
+                            (viewport_offset.y as i32 + viewport.top_px) as f32,
+                            viewport.width_px as f32,
+                            viewport.height_px as f32,
+                            0.,
+                            1.,
                         );
 
-                        render_pass.draw_indexed(
-                            vertex_offset..(vertex_offset + command.vertices_count as u32),
-                            0,
-                            0..1,
+                        command.callback.cb().render(
+                            info,
+                            &mut render_pass,
+                            render_target_render_entity,
+                            key,
+                            world,
                         );
-
-                        vertex_offset += command.vertices_count as u32;
-                    }
-                    DrawPrimitive::PaintCallback(command) => {
-                        let info = egui::PaintCallbackInfo {
-                            viewport: command.rect,
-                            clip_rect: draw_command.clip_rect,
-                            pixels_per_point: data.pixels_per_point,
-                            screen_size_px: [physical_width, physical_height],
-                        };
-
-                        let viewport = info.viewport_in_pixels();
-                        if viewport.width_px > 0 && viewport.height_px > 0 {
-                            requires_reset = true;
-                            render_pass.set_viewport(
-                                viewport.left_px as f32,
-                                viewport.top_px as f32,
-                                viewport.width_px as f32,
-                                viewport.height_px as f32,
-                                0.,
-                                1.,
-                            );
-
-                            command.callback.cb().render(
-                                info,
-                                &mut render_pass,
-                                render_target_render_entity,
-                                key,
-                                world,
-                            );
-                        }
                     }
                 }
+                // Handled at the beginning of the loop.
+                DrawPrimitive::SetBlendState(_) => {}
             }
+        }
 
-            drop(render_pass);
-            command_encoder.finish()
-        });
-
-        Ok(())
+        data.pass_recorded.store(true, Ordering::Relaxed);
     }
 }
 
@@ -1,19 +1,24 @@
 #[cfg(target_arch = "wasm32")]
 use crate::text_agent::{is_mobile_safari, update_text_agent};
 use crate::{
-    helpers::{vec2_into_egui_pos2, QueryHelper},
-    EguiContext, EguiContextSettings, EguiGlobalSettings, EguiInput, EguiOutput,
+    helpers::{vec2_into_egui_pos2, vec2_into_egui_vec2, QueryHelper},
+    EguiContext, EguiContextSettings, EguiGlobalSettings, EguiInput, EguiOutput, EguiWantsInput,
+    RenderTargetSize,
 };
 use bevy_ecs::prelude::*;
 use bevy_input::{
+    gestures::PinchGesture,
     keyboard::{Key, KeyboardFocusLost, KeyboardInput},
-    mouse::{MouseButton, MouseButtonInput, MouseScrollUnit, MouseWheel},
+    mouse::{MouseButton, MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
     touch::TouchInput,
-    ButtonState,
+    ButtonInput, ButtonState,
 };
 use bevy_log as log;
 use bevy_time::{Real, Time};
-use bevy_window::{CursorMoved, Ime, Window};
+use bevy_utils::{HashMap, HashSet};
+use bevy_window::{
+    CursorEntered, CursorGrabMode, CursorLeft, CursorMoved, Ime, Window, WindowEvent, WindowFocused,
+};
 use egui::Modifiers;
 
 /// Cached pointer position, used to populate [`egui::Event::PointerButton`] events.
@@ -30,6 +35,16 @@ pub struct EguiContextPointerTouchId {
     pub pointer_touch_id: Option<u64>,
 }
 
+/// Routes touches with the specified id to a context, instead of the window context the touches happen in.
+///
+/// Egui supports a single pointer per context. To let several users interact with the same window simultaneously
+/// (e.g. on a touch table), spawn a context per user that renders on top of the window (see `EguiLayer`) and insert
+/// this component once a touch that belongs to a user starts (for example, by checking its position in a system
+/// that reads [`TouchInput`] events and runs before [`crate::EguiInputSet::FocusContext`]). Each context will
+/// then receive its own touches and translate them into independent pointer events.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EguiBoundTouch(pub u64);
+
 /// Indicates whether [IME](https://en.wikipedia.org/wiki/Input_method) is enabled or disabled to avoid sending event duplicates.
 #[derive(Component, Default)]
 pub struct EguiContextImeState {
@@ -46,6 +61,118 @@ pub struct EguiInputEvent {
     pub event: egui::Event,
 }
 
+/// Merges the events emitted by the [`crate::EguiInputSet`] systems into a single timeline, ordered by the arrival
+/// of the Bevy events they originate from.
+///
+/// `bevy_winit` sends every input event both as a typed event (e.g. [`CursorMoved`]) and wrapped into [`WindowEvent`],
+/// the latter keeping the order the events were reported in by the OS across all the event types.
+/// [`read_window_events_system`] stores the [`WindowEvent`] events of the current frame, the input systems look up
+/// the position of every typed event they read among them and push the resulting events to the timeline,
+/// [`flush_egui_input_timeline_system`] sends them as [`EguiInputEvent`] sorted by that position.
+///
+/// Typed events that don't have a matching [`WindowEvent`] (for example, sent by an app directly) are ordered after
+/// the rest, events that don't originate from a Bevy event (such as [`egui::Event::PointerGone`] sent when the cursor
+/// gets grabbed) go first. Events coming from the same Bevy event keep the order they were pushed in.
+#[derive(Resource, Default)]
+pub struct EguiInputTimeline {
+    window_events: Vec<WindowEvent>,
+    events: Vec<(usize, EguiInputEvent)>,
+    frame_start_pointer_positions: HashMap<Entity, egui::Pos2>,
+}
+
+/// Tracks which [`WindowEvent`] events have already been matched by a system, see [`EguiInputTimeline::arrival`].
+pub(crate) struct EguiInputArrivals(Vec<bool>);
+
+impl EguiInputTimeline {
+    /// Position of the events that don't originate from a Bevy event.
+    pub(crate) const FRAME_START: usize = 0;
+
+    pub(crate) fn arrivals(&self) -> EguiInputArrivals {
+        EguiInputArrivals(vec![false; self.window_events.len()])
+    }
+
+    /// Returns the position of a typed Bevy event in the timeline.
+    ///
+    /// Equal events are matched in the order of arrival, so every system reading the same event type
+    /// has to use its own [`EguiInputArrivals`].
+    pub(crate) fn arrival(
+        &self,
+        arrivals: &mut EguiInputArrivals,
+        event: impl Into<WindowEvent>,
+    ) -> usize {
+        let event = event.into();
+        let matched = self
+            .window_events
+            .iter()
+            .zip(arrivals.0.iter_mut())
+            .position(|(window_event, matched)| !*matched && *window_event == event);
+        match matched {
+            Some(index) => {
+                arrivals.0[index] = true;
+                index + 1
+            }
+            None => self.frame_end(),
+        }
+    }
+
+    /// Position of the events that don't have a matching [`WindowEvent`].
+    pub(crate) fn frame_end(&self) -> usize {
+        self.window_events.len() + 1
+    }
+
+    /// Pushes an event to the timeline.
+    pub(crate) fn send(&mut self, arrival: usize, event: EguiInputEvent) {
+        self.events.push((arrival, event));
+    }
+
+    /// Remembers the pointer position of a context before its first pointer move within the current frame.
+    pub(crate) fn record_pointer_position(&mut self, context: Entity, position: egui::Pos2) {
+        self.frame_start_pointer_positions
+            .entry(context)
+            .or_insert(position);
+    }
+
+    /// Returns the pointer position of a context at the specified position of the timeline (taking into account
+    /// the events pushed so far), or [`None`] if the pointer hasn't been moved within the current frame.
+    pub(crate) fn pointer_position(&self, context: Entity, arrival: usize) -> Option<egui::Pos2> {
+        self.events
+            .iter()
+            .filter(|(event_arrival, event)| *event_arrival <= arrival && event.context == context)
+            .filter_map(|(event_arrival, event)| match event.event {
+                egui::Event::PointerMoved(position) => Some((*event_arrival, position)),
+                _ => None,
+            })
+            // `max_by_key` returns the last of the equal elements.
+            .max_by_key(|(event_arrival, _)| *event_arrival)
+            .map(|(_, position)| position)
+            .or_else(|| self.frame_start_pointer_positions.get(&context).copied())
+    }
+}
+
+/// Reads [`WindowEvent`] events to order the events of [`EguiInputTimeline`].
+pub fn read_window_events_system(
+    mut window_event_reader: EventReader<WindowEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
+) {
+    let timeline = timeline.as_mut();
+    timeline.window_events.clear();
+    timeline
+        .window_events
+        .extend(window_event_reader.read().cloned());
+    timeline.frame_start_pointer_positions.clear();
+}
+
+/// Sends the events of [`EguiInputTimeline`] as [`EguiInputEvent`] in the order of arrival.
+pub fn flush_egui_input_timeline_system(
+    mut timeline: ResMut<EguiInputTimeline>,
+    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+) {
+    let mut events = std::mem::take(&mut timeline.events);
+    // The sort is stable, so the events coming from the same Bevy event keep their order.
+    events.sort_by_key(|(arrival, _)| *arrival);
+    egui_input_event_writer.send_batch(events.into_iter().map(|(_, event)| event));
+}
+
 #[derive(Resource)]
 /// Insert this resource when a pointer hovers over a non-window (e.g. world-space) [`EguiContext`] entity.
 /// Also, make sure to update an [`EguiContextPointerPosition`] component of a hovered entity.
@@ -173,17 +300,90 @@ pub fn write_modifiers_keys_state_system(
     }
 }
 
-/// Reads [`MouseButtonInput`] events and wraps them into [`EguiInputEvent`] (only for window contexts).
+/// Returns `true` if the cursor of a window is grabbed for camera control or similar (see [`CursorGrabMode`]),
+/// i.e. its position is meaningless for the UI: the cursor is either locked or confined and hidden.
+fn is_cursor_grabbed(window: &Window) -> bool {
+    match window.cursor_options.grab_mode {
+        CursorGrabMode::None => false,
+        CursorGrabMode::Confined => !window.cursor_options.visible,
+        CursorGrabMode::Locked => true,
+    }
+}
+
+/// Reads [`CursorMoved`] events and wraps them into [`EguiInputEvent`] (only for window contexts).
+///
+/// Every event is forwarded as a separate [`egui::Event::PointerMoved`] in the order of arrival, so that Egui sees
+/// the full pointer path even if a high polling rate mouse reports several positions per frame (which matters
+/// for painting widgets). [`EguiContextPointerPosition`] is updated to the latest position.
+///
+/// While the cursor of a window is grabbed (locked, or confined and hidden), the pointer position isn't forwarded:
+/// [`egui::Event::PointerGone`] is sent once the grab starts, so that no widget stays hovered. Once the grab is released,
+/// the current cursor position is sent.
+///
+/// While the cursor is over a layer restricted to a rect (see [`crate::EguiLayerRect`]), the pointer input goes
+/// to the layer (see [`update_layer_rect_hovered_context_system`]), so the window context gets
+/// [`egui::Event::PointerGone`] instead of the pointer moves.
 pub fn write_window_pointer_moved_events_system(
     mut cursor_moved_reader: EventReader<CursorMoved>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
     mut egui_contexts: Query<
-        (&EguiContextSettings, &mut EguiContextPointerPosition),
-        (With<EguiContext>, With<Window>),
+        (
+            Entity,
+            &EguiContextSettings,
+            &mut EguiContextPointerPosition,
+            &Window,
+        ),
+        With<EguiContext>,
     >,
+    #[cfg(feature = "render")] layer_rects: Query<(&crate::EguiLayer, &crate::EguiLayerRect)>,
+    mut grabbed_windows: Local<HashSet<Entity>>,
+    #[cfg(feature = "render")] mut covered_windows: Local<HashSet<Entity>>,
 ) {
+    for (entity, context_settings, mut context_pointer_position, window) in egui_contexts.iter_mut()
+    {
+        if !context_settings
+            .input_system_settings
+            .run_write_window_pointer_moved_events_system
+        {
+            continue;
+        }
+
+        let is_grabbed = is_cursor_grabbed(window);
+        if is_grabbed && grabbed_windows.insert(entity) {
+            timeline.send(
+                EguiInputTimeline::FRAME_START,
+                EguiInputEvent {
+                    context: entity,
+                    event: egui::Event::PointerGone,
+                },
+            );
+        } else if !is_grabbed && grabbed_windows.remove(&entity) {
+            if let Some(position) = window.cursor_position() {
+                let pointer_position =
+                    vec2_into_egui_pos2(position / context_settings.input_scale_factor());
+                timeline.record_pointer_position(entity, context_pointer_position.position);
+                context_pointer_position.position = pointer_position;
+                timeline.send(
+                    EguiInputTimeline::FRAME_START,
+                    EguiInputEvent {
+                        context: entity,
+                        event: egui::Event::PointerMoved(pointer_position),
+                    },
+                );
+            }
+        }
+    }
+    grabbed_windows.retain(|window| egui_contexts.contains(*window));
+    #[cfg(feature = "render")]
+    covered_windows.retain(|window| egui_contexts.contains(*window));
+
+    let mut arrivals = timeline.arrivals();
     for event in cursor_moved_reader.read() {
-        let Some((context_settings, mut context_pointer_position)) =
+        let arrival = timeline.arrival(&mut arrivals, event.clone());
+        if grabbed_windows.contains(&event.window) {
+            continue;
+        }
+        let Some((_, context_settings, mut context_pointer_position, _)) =
             egui_contexts.get_some_mut(event.window)
         else {
             continue;
@@ -196,39 +396,243 @@ pub fn write_window_pointer_moved_events_system(
             continue;
         }
 
-        let scale_factor = context_settings.scale_factor;
+        #[cfg(feature = "render")]
+        {
+            let is_over_layer_rect = layer_rects.iter().any(|(layer, rect)| {
+                layer.window == event.window && rect.0.contains(event.position)
+            });
+            if is_over_layer_rect {
+                if covered_windows.insert(event.window) {
+                    timeline.send(
+                        arrival,
+                        EguiInputEvent {
+                            context: event.window,
+                            event: egui::Event::PointerGone,
+                        },
+                    );
+                }
+                continue;
+            }
+            covered_windows.remove(&event.window);
+        }
+
+        let scale_factor = context_settings.input_scale_factor();
         let pointer_position = vec2_into_egui_pos2(event.position / scale_factor);
+        timeline.record_pointer_position(event.window, context_pointer_position.position);
         context_pointer_position.position = pointer_position;
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context: event.window,
+                event: egui::Event::PointerMoved(pointer_position),
+            },
+        );
+    }
+}
+
+/// Reads [`CursorMoved`] and [`CursorLeft`] events to route pointer input to contexts restricted with [`crate::EguiLayerRect`].
+///
+/// When the cursor is over such a context, the context becomes the [`HoveredNonWindowEguiContext`] and its
+/// [`EguiContextPointerPosition`] is updated (relative to the rect). If several rects overlap, the layer with the highest
+/// [`crate::EguiLayer::order`] wins. When the cursor leaves the rect, [`egui::Event::PointerGone`] is sent and the resource
+/// gets removed (the resource is never removed if it points to a context that isn't restricted with a rect).
+#[cfg(feature = "render")]
+pub fn update_layer_rect_hovered_context_system(
+    mut commands: Commands,
+    hovered_non_window_egui_context: Option<Res<HoveredNonWindowEguiContext>>,
+    mut cursor_moved_reader: EventReader<CursorMoved>,
+    mut cursor_left_reader: EventReader<CursorLeft>,
+    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut egui_contexts: Query<(
+        Entity,
+        &EguiContextSettings,
+        &mut EguiContextPointerPosition,
+        &crate::EguiLayer,
+        &crate::EguiLayerRect,
+    )>,
+) {
+    let initially_hovered = hovered_non_window_egui_context
+        .as_deref()
+        .map(|hovered| hovered.0);
+    let mut hovered = initially_hovered;
+    for event in cursor_moved_reader.read() {
+        let topmost = egui_contexts
+            .iter()
+            .filter(|(_, _, _, layer, rect)| {
+                layer.window == event.window && rect.0.contains(event.position)
+            })
+            .max_by_key(|(_, _, _, layer, _)| layer.order)
+            .map(|(entity, _, _, _, rect)| (entity, rect.0.min));
+
+        if let Some((entity, rect_min)) = topmost {
+            let Some((_, context_settings, mut context_pointer_position, _, _)) =
+                egui_contexts.get_some_mut(entity)
+            else {
+                continue;
+            };
+            context_pointer_position.position = vec2_into_egui_pos2(
+                (event.position - rect_min) / context_settings.input_scale_factor(),
+            );
+            hovered = Some(entity);
+        } else if hovered
+            .and_then(|entity| egui_contexts.get_some(entity))
+            .is_some_and(|(_, _, _, layer, _)| layer.window == event.window)
+        {
+            hovered = None;
+        }
+    }
+    for event in cursor_left_reader.read() {
+        if hovered
+            .and_then(|entity| egui_contexts.get_some(entity))
+            .is_some_and(|(_, _, _, layer, _)| layer.window == event.window)
+        {
+            hovered = None;
+        }
+    }
+
+    if hovered == initially_hovered {
+        return;
+    }
+    if let Some(entity) = initially_hovered.filter(|entity| egui_contexts.contains(*entity)) {
         egui_input_event_writer.send(EguiInputEvent {
-            context: event.window,
-            event: egui::Event::PointerMoved(pointer_position),
+            context: entity,
+            event: egui::Event::PointerGone,
         });
     }
+    match hovered {
+        Some(entity) => commands.insert_resource(HoveredNonWindowEguiContext(entity)),
+        None => commands.remove_resource::<HoveredNonWindowEguiContext>(),
+    }
+}
+
+/// Reads [`MouseMotion`] events and wraps them into [`EguiInputEvent`] (only for window contexts).
+///
+/// [`CursorMoved`] events stop coming once the cursor leaves a window. To avoid freezing drags (sliders, scroll bars, etc.)
+/// at the window edge, the system keeps moving the pointer by relative mouse motion while any mouse button is held
+/// and the cursor is outside of a window. Absolute positioning is restored once the cursor re-enters the window.
+pub fn write_window_pointer_motion_events_system(
+    mut cursor_left_reader: EventReader<CursorLeft>,
+    mut cursor_entered_reader: EventReader<CursorEntered>,
+    mut mouse_motion_reader: EventReader<MouseMotion>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mut timeline: ResMut<EguiInputTimeline>,
+    mut egui_contexts: Query<
+        (
+            Entity,
+            &EguiContextSettings,
+            &mut EguiContextPointerPosition,
+            &Window,
+        ),
+        With<EguiContext>,
+    >,
+    mut windows_left: Local<HashSet<Entity>>,
+) {
+    for event in cursor_left_reader.read() {
+        windows_left.insert(event.window);
+    }
+    for event in cursor_entered_reader.read() {
+        windows_left.remove(&event.window);
+    }
+    windows_left.retain(|window| egui_contexts.contains(*window));
+
+    // The summed motion is placed in the timeline at the last of the events.
+    let mut arrivals = timeline.arrivals();
+    let mut arrival = EguiInputTimeline::FRAME_START;
+    let mut delta = bevy_math::Vec2::ZERO;
+    for event in mouse_motion_reader.read() {
+        arrival = timeline.arrival(&mut arrivals, *event);
+        delta += event.delta;
+    }
+    if windows_left.is_empty() || delta == bevy_math::Vec2::ZERO {
+        return;
+    }
+    if !mouse_button_input.get_pressed().any(|_| true) {
+        return;
+    }
+
+    for &window_entity in windows_left.iter() {
+        let Some((entity, context_settings, mut context_pointer_position, window)) =
+            egui_contexts.get_some_mut(window_entity)
+        else {
+            continue;
+        };
+        if is_cursor_grabbed(window) {
+            continue;
+        }
+        if !context_settings
+            .input_system_settings
+            .run_write_window_pointer_motion_events_system
+        {
+            continue;
+        }
+
+        // Mouse motion deltas are reported in physical pixels.
+        let delta = vec2_into_egui_vec2(
+            delta / window.scale_factor() / context_settings.input_scale_factor(),
+        );
+        let pointer_position = context_pointer_position.position + delta;
+        timeline.record_pointer_position(entity, context_pointer_position.position);
+        context_pointer_position.position = pointer_position;
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context: entity,
+                event: egui::Event::MouseMoved(delta),
+            },
+        );
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context: entity,
+                event: egui::Event::PointerMoved(pointer_position),
+            },
+        );
+    }
 }
 
 /// Reads [`MouseButtonInput`] events and wraps them into [`EguiInputEvent`], can redirect events to [`HoveredNonWindowEguiContext`],
 /// inserts, updates or removes the [`FocusedNonWindowEguiContext`] resource based on a hovered context.
+///
+/// Events aren't sent to window contexts while their cursor is grabbed (see [`write_window_pointer_moved_events_system`]).
+///
+/// Pressing the middle mouse button over a context that has a focused text field pastes the primary selection
+/// (see [`crate::EguiClipboard::get_primary_selection`]), on platforms that have one.
+#[allow(clippy::too_many_arguments)]
 pub fn write_pointer_button_events_system(
     egui_global_settings: Res<EguiGlobalSettings>,
     mut commands: Commands,
     hovered_non_window_egui_context: Option<Res<HoveredNonWindowEguiContext>>,
     modifier_keys_state: Res<ModifierKeysState>,
+    #[cfg(all(feature = "manage_clipboard", not(target_arch = "wasm32")))]
+    mut egui_clipboard: ResMut<crate::EguiClipboard>,
     mut mouse_button_input_reader: EventReader<MouseButtonInput>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
-    egui_contexts: Query<(&EguiContextSettings, &EguiContextPointerPosition), With<EguiContext>>,
+    mut timeline: ResMut<EguiInputTimeline>,
+    egui_contexts: Query<(
+        &EguiContext,
+        &EguiContextSettings,
+        &EguiContextPointerPosition,
+        &EguiWantsInput,
+        Option<&Window>,
+    )>,
 ) {
     let modifiers = modifier_keys_state.to_egui_modifiers();
+    let mut arrivals = timeline.arrivals();
     for event in mouse_button_input_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, *event);
         let hovered_context = hovered_non_window_egui_context
             .as_deref()
             .map_or(event.window, |hovered| hovered.0);
 
-        let Some((context_settings, context_pointer_position)) =
+        let Some((_context, context_settings, context_pointer_position, _wants_input, window)) =
             egui_contexts.get_some(hovered_context)
         else {
             continue;
         };
 
+        if window.is_some_and(is_cursor_grabbed) {
+            continue;
+        }
+
         if !context_settings
             .input_system_settings
             .run_write_pointer_button_events_system
@@ -251,15 +655,39 @@ pub fn write_pointer_button_events_system(
             ButtonState::Pressed => true,
             ButtonState::Released => false,
         };
-        egui_input_event_writer.send(EguiInputEvent {
-            context: hovered_context,
-            event: egui::Event::PointerButton {
-                pos: context_pointer_position.position,
-                button,
-                pressed,
-                modifiers,
+        // The button is pressed where the pointer was at the moment, not where it ended up by the end of the frame.
+        let pos = timeline
+            .pointer_position(hovered_context, arrival)
+            .unwrap_or(context_pointer_position.position);
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context: hovered_context,
+                event: egui::Event::PointerButton {
+                    pos,
+                    button,
+                    pressed,
+                    modifiers,
+                },
             },
-        });
+        );
+
+        #[cfg(all(feature = "manage_clipboard", not(target_arch = "wasm32")))]
+        if button == egui::PointerButton::Middle
+            && pressed
+            && _wants_input.keyboard
+            && _context.is_pointer_over_area()
+        {
+            if let Some(contents) = egui_clipboard.get_primary_selection() {
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context: hovered_context,
+                        event: egui::Event::Paste(contents),
+                    },
+                );
+            }
+        }
 
         // If we are hovering over some UI in world space, we want to mark it as focused on mouse click.
         if egui_global_settings.enable_focused_non_window_context_updates && pressed {
@@ -278,7 +706,7 @@ pub fn write_pointer_button_events_system(
 pub fn write_non_window_pointer_moved_events_system(
     hovered_non_window_egui_context: Option<Res<HoveredNonWindowEguiContext>>,
     mut cursor_moved_reader: EventReader<CursorMoved>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
     egui_contexts: Query<(&EguiContextSettings, &EguiContextPointerPosition), With<EguiContext>>,
 ) {
     if cursor_moved_reader.is_empty() {
@@ -305,33 +733,77 @@ pub fn write_non_window_pointer_moved_events_system(
         return;
     }
 
-    egui_input_event_writer.send(EguiInputEvent {
-        context: *hovered_non_window_egui_context,
-        event: egui::Event::PointerMoved(context_pointer_position.position),
-    });
+    // The position is updated once per frame, so the move goes before the rest of the events.
+    timeline.send(
+        EguiInputTimeline::FRAME_START,
+        EguiInputEvent {
+            context: *hovered_non_window_egui_context,
+            event: egui::Event::PointerMoved(context_pointer_position.position),
+        },
+    );
 }
 
-/// Reads [`MouseWheel`] events and wraps them into [`EguiInputEvent`], can redirect events to [`HoveredNonWindowEguiContext`].
+/// Reads [`MouseWheel`] and [`PinchGesture`] events and wraps them into [`EguiInputEvent`], can redirect events
+/// to [`HoveredNonWindowEguiContext`].
+///
+/// Pixel deltas (reported by trackpads) are converted into points, line deltas are passed as is,
+/// unless [`EguiContextSettings::force_pixel_scroll_unit`] is set. If [`EguiContextSettings::zoom_step`] is set,
+/// wheel events with Ctrl (or Cmd on macOS) held are translated into [`egui::Event::Zoom`].
+/// Pinch gestures are always translated into [`egui::Event::Zoom`], they are sent to the focused window
+/// (pinch gestures don't carry a window).
 pub fn write_mouse_wheel_events_system(
     modifier_keys_state: Res<ModifierKeysState>,
     hovered_non_window_egui_context: Option<Res<HoveredNonWindowEguiContext>>,
     mut mouse_wheel_reader: EventReader<MouseWheel>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
-    egui_contexts: Query<&EguiContextSettings, With<EguiContext>>,
+    mut pinch_gesture_reader: EventReader<PinchGesture>,
+    mut timeline: ResMut<EguiInputTimeline>,
+    egui_contexts: Query<(&EguiContext, &EguiContextSettings, &RenderTargetSize)>,
+    windows: Query<(Entity, &Window)>,
 ) {
     let modifiers = modifier_keys_state.to_egui_modifiers();
-    for event in mouse_wheel_reader.read() {
-        let delta = egui::vec2(event.x, event.y);
-        let unit = match event.unit {
-            MouseScrollUnit::Line => egui::MouseWheelUnit::Line,
-            MouseScrollUnit::Pixel => egui::MouseWheelUnit::Point,
+    let mut arrivals = timeline.arrivals();
+    for event in pinch_gesture_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, *event);
+        let Some(context) = hovered_non_window_egui_context
+            .as_deref()
+            .map(|hovered| hovered.0)
+            .or_else(|| {
+                windows
+                    .iter()
+                    .find_map(|(entity, window)| window.focused.then_some(entity))
+            })
+        else {
+            continue;
+        };
+
+        let Some((_, context_settings, _)) = egui_contexts.get_some(context) else {
+            continue;
         };
 
+        if !context_settings
+            .input_system_settings
+            .run_write_mouse_wheel_events_system
+        {
+            continue;
+        }
+
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context,
+                event: egui::Event::Zoom(event.0.exp()),
+            },
+        );
+    }
+
+    for event in mouse_wheel_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, *event);
         let context = hovered_non_window_egui_context
             .as_deref()
             .map_or(event.window, |hovered| hovered.0);
 
-        let Some(context_settings) = egui_contexts.get_some(context) else {
+        let Some((ctx, context_settings, render_target_size)) = egui_contexts.get_some(context)
+        else {
             continue;
         };
 
@@ -342,14 +814,56 @@ pub fn write_mouse_wheel_events_system(
             continue;
         }
 
-        egui_input_event_writer.send(EguiInputEvent {
-            context,
-            event: egui::Event::MouseWheel {
-                unit,
-                delta,
-                modifiers,
+        let delta = egui::vec2(event.x, event.y);
+        // Reading options doesn't require a mutable borrow (`EguiContext::get` is gated with `immutable_ctx`).
+        let line_scroll_speed = || ctx.ctx.options(|o| o.line_scroll_speed);
+
+        if let Some(zoom_step) = context_settings.zoom_step {
+            if modifiers.ctrl || modifiers.command {
+                // Pixel deltas are converted into lines, so that trackpads zoom at a similar rate.
+                let lines = match event.unit {
+                    MouseScrollUnit::Line => delta.y,
+                    MouseScrollUnit::Pixel => {
+                        delta.y
+                            / (render_target_size.scale_factor * context_settings.scale_factor)
+                            / line_scroll_speed()
+                    }
+                };
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::Zoom(zoom_step.powf(lines)),
+                    },
+                );
+                continue;
+            }
+        }
+
+        let (unit, delta) = match event.unit {
+            MouseScrollUnit::Line if context_settings.force_pixel_scroll_unit => {
+                (egui::MouseWheelUnit::Point, delta * line_scroll_speed())
+            }
+            MouseScrollUnit::Line => (egui::MouseWheelUnit::Line, delta),
+            // Pixel deltas are reported in physical pixels.
+            MouseScrollUnit::Pixel if context_settings.scale_pixel_scroll_deltas => (
+                egui::MouseWheelUnit::Point,
+                delta / (render_target_size.scale_factor * context_settings.scale_factor),
+            ),
+            MouseScrollUnit::Pixel => (egui::MouseWheelUnit::Point, delta),
+        };
+
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context,
+                event: egui::Event::MouseWheel {
+                    unit,
+                    delta,
+                    modifiers,
+                },
             },
-        });
+        );
     }
 }
 
@@ -357,18 +871,18 @@ pub fn write_mouse_wheel_events_system(
 pub fn write_keyboard_input_events_system(
     modifier_keys_state: Res<ModifierKeysState>,
     focused_non_window_egui_context: Option<Res<FocusedNonWindowEguiContext>>,
-    #[cfg(all(
-        feature = "manage_clipboard",
-        not(target_os = "android"),
-        not(target_arch = "wasm32")
-    ))]
+    #[cfg(all(feature = "manage_clipboard", not(target_arch = "wasm32")))]
     mut egui_clipboard: ResMut<crate::EguiClipboard>,
+    #[cfg(all(feature = "manage_clipboard", not(target_arch = "wasm32")))]
+    mut image_paste_event_writer: EventWriter<crate::EguiImagePasteEvent>,
     mut keyboard_input_reader: EventReader<KeyboardInput>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
     egui_contexts: Query<&EguiContextSettings, With<EguiContext>>,
 ) {
     let modifiers = modifier_keys_state.to_egui_modifiers();
+    let mut arrivals = timeline.arrivals();
     for event in keyboard_input_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, event.clone());
         let context = focused_non_window_egui_context
             .as_deref()
             .map_or(event.window, |context| context.0);
@@ -387,16 +901,22 @@ pub fn write_keyboard_input_events_system(
         if modifier_keys_state.text_input_is_allowed() && event.state.is_pressed() {
             match &event.logical_key {
                 Key::Character(char) if char.matches(char::is_control).count() == 0 => {
-                    egui_input_event_writer.send(EguiInputEvent {
-                        context,
-                        event: egui::Event::Text(char.to_string()),
-                    });
+                    timeline.send(
+                        arrival,
+                        EguiInputEvent {
+                            context,
+                            event: egui::Event::Text(char.to_string()),
+                        },
+                    );
                 }
                 Key::Space => {
-                    egui_input_event_writer.send(EguiInputEvent {
-                        context,
-                        event: egui::Event::Text(" ".to_string()),
-                    });
+                    timeline.send(
+                        arrival,
+                        EguiInputEvent {
+                            context,
+                            event: egui::Event::Text(" ".to_string()),
+                        },
+                    );
                 }
                 _ => (),
             }
@@ -416,38 +936,64 @@ pub fn write_keyboard_input_events_system(
             modifiers,
             physical_key,
         };
-        egui_input_event_writer.send(EguiInputEvent {
-            context,
-            event: egui_event,
-        });
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context,
+                event: egui_event,
+            },
+        );
 
         // We also check that it's a `ButtonState::Pressed` event, as we don't want to
         // copy, cut or paste on the key release.
-        #[cfg(all(
-            feature = "manage_clipboard",
-            not(target_os = "android"),
-            not(target_arch = "wasm32")
-        ))]
+        #[cfg(all(feature = "manage_clipboard", not(target_arch = "wasm32")))]
         if modifiers.command && event.state.is_pressed() {
             match key {
                 egui::Key::C => {
-                    egui_input_event_writer.send(EguiInputEvent {
-                        context,
-                        event: egui::Event::Copy,
-                    });
+                    timeline.send(
+                        arrival,
+                        EguiInputEvent {
+                            context,
+                            event: egui::Event::Copy,
+                        },
+                    );
                 }
                 egui::Key::X => {
-                    egui_input_event_writer.send(EguiInputEvent {
-                        context,
-                        event: egui::Event::Cut,
-                    });
+                    timeline.send(
+                        arrival,
+                        EguiInputEvent {
+                            context,
+                            event: egui::Event::Cut,
+                        },
+                    );
                 }
                 egui::Key::V => {
-                    if let Some(contents) = egui_clipboard.get_text() {
-                        egui_input_event_writer.send(EguiInputEvent {
-                            context,
-                            event: egui::Event::Text(contents),
-                        });
+                    // Egui widgets accept only text, so text is preferred unless the app asks otherwise.
+                    // Images are passed to the app via `EguiImagePasteEvent`. An empty clipboard doesn't
+                    // produce any events.
+                    let preferred_image = context_settings
+                        .prefer_image_paste
+                        .then(|| egui_clipboard.get_image())
+                        .flatten();
+                    if let Some(image) = preferred_image {
+                        image_paste_event_writer
+                            .send(crate::EguiImagePasteEvent { context, image });
+                    } else if let Some(contents) = egui_clipboard
+                        .get_text()
+                        .filter(|contents| !contents.is_empty())
+                    {
+                        timeline.send(
+                            arrival,
+                            EguiInputEvent {
+                                context,
+                                event: egui::Event::Paste(contents),
+                            },
+                        );
+                    } else if !context_settings.prefer_image_paste {
+                        if let Some(image) = egui_clipboard.get_image() {
+                            image_paste_event_writer
+                                .send(crate::EguiImagePasteEvent { context, image });
+                        }
                     }
                 }
                 _ => {}
@@ -460,7 +1006,7 @@ pub fn write_keyboard_input_events_system(
 pub fn write_ime_events_system(
     focused_non_window_egui_context: Option<Res<FocusedNonWindowEguiContext>>,
     mut ime_reader: EventReader<Ime>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
     mut egui_contexts: Query<
         (
             Entity,
@@ -471,7 +1017,9 @@ pub fn write_ime_events_system(
         With<EguiContext>,
     >,
 ) {
+    let mut arrivals = timeline.arrivals();
     for event in ime_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, event.clone());
         let window = match &event {
             Ime::Preedit { window, .. }
             | Ime::Commit { window, .. }
@@ -495,68 +1043,218 @@ pub fn write_ime_events_system(
             continue;
         }
 
-        let ime_event_enable =
-            |ime_state: &mut EguiContextImeState,
-             egui_input_event_writer: &mut EventWriter<EguiInputEvent>| {
-                if !ime_state.has_sent_ime_enabled {
-                    egui_input_event_writer.send(EguiInputEvent {
+        let ime_event_enable = |ime_state: &mut EguiContextImeState,
+                                timeline: &mut EguiInputTimeline| {
+            if !ime_state.has_sent_ime_enabled {
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
                         context,
                         event: egui::Event::Ime(egui::ImeEvent::Enabled),
-                    });
-                    ime_state.has_sent_ime_enabled = true;
-                }
-            };
+                    },
+                );
+                ime_state.has_sent_ime_enabled = true;
+            }
+        };
 
-        let ime_event_disable =
-            |ime_state: &mut EguiContextImeState,
-             egui_input_event_writer: &mut EventWriter<EguiInputEvent>| {
-                if !ime_state.has_sent_ime_enabled {
-                    egui_input_event_writer.send(EguiInputEvent {
+        let ime_event_disable = |ime_state: &mut EguiContextImeState,
+                                 timeline: &mut EguiInputTimeline| {
+            if !ime_state.has_sent_ime_enabled {
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
                         context,
                         event: egui::Event::Ime(egui::ImeEvent::Disabled),
-                    });
-                    ime_state.has_sent_ime_enabled = false;
-                }
-            };
+                    },
+                );
+                ime_state.has_sent_ime_enabled = false;
+            }
+        };
 
         // Aligned with the egui-winit implementation: https://github.com/emilk/egui/blob/0f2b427ff4c0a8c68f6622ec7d0afb7ba7e71bba/crates/egui-winit/src/lib.rs#L348
         match event {
             Ime::Enabled { window: _ } => {
-                ime_event_enable(&mut ime_state, &mut egui_input_event_writer);
+                ime_event_enable(&mut ime_state, &mut timeline);
             }
             Ime::Preedit {
                 value,
                 window: _,
                 cursor: _,
             } => {
-                ime_event_enable(&mut ime_state, &mut egui_input_event_writer);
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::Ime(egui::ImeEvent::Preedit(value.clone())),
-                });
+                ime_event_enable(&mut ime_state, &mut timeline);
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::Ime(egui::ImeEvent::Preedit(value.clone())),
+                    },
+                );
             }
             Ime::Commit { value, window: _ } => {
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::Ime(egui::ImeEvent::Commit(value.clone())),
-                });
-                ime_event_disable(&mut ime_state, &mut egui_input_event_writer);
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::Ime(egui::ImeEvent::Commit(value.clone())),
+                    },
+                );
+                ime_event_disable(&mut ime_state, &mut timeline);
             }
             Ime::Disabled { window: _ } => {
-                ime_event_disable(&mut ime_state, &mut egui_input_event_writer);
+                ime_event_disable(&mut ime_state, &mut timeline);
             }
         }
     }
 }
 
+/// Reads [`WindowFocused`] events and wraps them into [`EguiInputEvent`].
+///
+/// When a window loses focus, [`egui::Event::PointerGone`] is sent as well, so that widgets don't stay hovered.
+pub fn write_window_focus_events_system(
+    mut window_focused_reader: EventReader<WindowFocused>,
+    mut timeline: ResMut<EguiInputTimeline>,
+    egui_contexts: Query<(), (With<EguiContext>, With<Window>)>,
+) {
+    let mut arrivals = timeline.arrivals();
+    for event in window_focused_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, event.clone());
+        if !egui_contexts.contains(event.window) {
+            continue;
+        }
+
+        timeline.send(
+            arrival,
+            EguiInputEvent {
+                context: event.window,
+                event: egui::Event::WindowFocused(event.focused),
+            },
+        );
+        if !event.focused {
+            timeline.send(
+                arrival,
+                EguiInputEvent {
+                    context: event.window,
+                    event: egui::Event::PointerGone,
+                },
+            );
+        }
+    }
+}
+
+/// Hands the [`bevy_a11y::Focus`] over to Egui when it lands on a context entity.
+///
+/// When the focus moves to a context, the widget focused in it (if any) is cleared and a [`egui::Key::Tab`]
+/// press (followed by a release) is sent, so Egui focuses its first widget (or the last one, if Shift is held). When the focus moves
+/// away from a context, its focused widget is cleared. This allows Tab cycling to hand off between Bevy UI
+/// and Egui, given that the application moves [`bevy_a11y::Focus`] to the context entity.
+#[cfg(feature = "accesskit")]
+pub fn write_accessibility_focus_events_system(
+    modifier_keys_state: Res<ModifierKeysState>,
+    focus: Option<Res<bevy_a11y::Focus>>,
+    mut last_focus: Local<Option<Entity>>,
+    mut timeline: ResMut<EguiInputTimeline>,
+    mut egui_contexts: Query<(&mut EguiContext, &EguiContextSettings)>,
+) {
+    let focused_entity = focus.as_deref().and_then(|focus| focus.0);
+    if *last_focus == focused_entity {
+        return;
+    }
+    let unfocused_entity = std::mem::replace(&mut *last_focus, focused_entity);
+
+    let clear_focus = |ctx: &mut egui::Context| {
+        ctx.memory_mut(|memory| {
+            if let Some(id) = memory.focused() {
+                memory.surrender_focus(id);
+            }
+        });
+    };
+
+    if let Some((mut ctx, _)) =
+        unfocused_entity.and_then(|entity| egui_contexts.get_some_mut(entity))
+    {
+        clear_focus(ctx.get_mut());
+    }
+
+    let Some(context) = focused_entity else {
+        return;
+    };
+    let Some((mut ctx, context_settings)) = egui_contexts.get_some_mut(context) else {
+        return;
+    };
+    if !context_settings
+        .input_system_settings
+        .run_write_accessibility_focus_events_system
+    {
+        return;
+    }
+
+    clear_focus(ctx.get_mut());
+    // The press is followed by a release, so that Egui doesn't consider Tab held down.
+    for pressed in [true, false] {
+        timeline.send(
+            EguiInputTimeline::FRAME_START,
+            EguiInputEvent {
+                context,
+                event: egui::Event::Key {
+                    key: egui::Key::Tab,
+                    physical_key: None,
+                    pressed,
+                    repeat: false,
+                    modifiers: modifier_keys_state.to_egui_modifiers(),
+                },
+            },
+        );
+    }
+}
+
+/// Reads [`bevy_a11y::ActionRequest`] events sent by assistive technologies (e.g. a screen reader activating a button)
+/// and wraps them into [`EguiInputEvent`] ([`egui::Event::AccessKitActionRequest`]).
+///
+/// The requests don't specify a window, so they are sent to every context: Egui only reacts to requests that target
+/// its own widgets, as AccessKit node ids are derived from [`egui::Id`]s.
+///
+/// Note that `bevy_egui` doesn't publish the AccessKit tree of contexts: it neither calls [`egui::Context::enable_accesskit`]
+/// nor forwards [`egui::PlatformOutput::accesskit_update`] to Bevy's AccessKit adapter. Until the tree is exposed
+/// (which is up to the app for now), assistive technologies can't see Egui widgets, so they can't target them either.
+#[cfg(feature = "accesskit")]
+pub fn write_accessibility_action_requests_system(
+    mut action_request_reader: EventReader<bevy_a11y::ActionRequest>,
+    mut timeline: ResMut<EguiInputTimeline>,
+    egui_contexts: Query<(Entity, &EguiContextSettings), With<EguiContext>>,
+) {
+    for request in action_request_reader.read() {
+        // Action requests aren't window events, they are ordered after the rest.
+        let arrival = timeline.frame_end();
+        for (context, context_settings) in egui_contexts.iter() {
+            if !context_settings
+                .input_system_settings
+                .run_write_accessibility_action_requests_system
+            {
+                continue;
+            }
+
+            timeline.send(
+                arrival,
+                EguiInputEvent {
+                    context,
+                    event: egui::Event::AccessKitActionRequest(request.0.clone()),
+                },
+            );
+        }
+    }
+}
+
 /// Reads [`TouchInput`] events and wraps them into [`EguiInputEvent`].
+///
+/// Touches bound with [`EguiBoundTouch`] are sent to the bound contexts.
+#[allow(clippy::too_many_arguments)]
 pub fn write_window_touch_events_system(
     mut commands: Commands,
     egui_global_settings: Res<EguiGlobalSettings>,
     hovered_non_window_egui_context: Option<Res<HoveredNonWindowEguiContext>>,
     modifier_keys_state: Res<ModifierKeysState>,
     mut touch_input_reader: EventReader<TouchInput>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
     mut egui_contexts: Query<
         (
             &EguiContextSettings,
@@ -564,22 +1262,30 @@ pub fn write_window_touch_events_system(
             &mut EguiContextPointerTouchId,
             &EguiOutput,
         ),
-        (With<EguiContext>, With<Window>),
+        With<EguiContext>,
     >,
+    bound_touches: Query<(Entity, &EguiBoundTouch)>,
 ) {
     let modifiers = modifier_keys_state.to_egui_modifiers();
+    let mut arrivals = timeline.arrivals();
     for event in touch_input_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, *event);
+        let bound_context = bound_touches
+            .iter()
+            .find_map(|(entity, bound_touch)| (bound_touch.0 == event.id).then_some(entity));
+        let context = bound_context.unwrap_or(event.window);
         let Some((
             context_settings,
             mut context_pointer_position,
             mut context_pointer_touch_id,
             output,
-        )) = egui_contexts.get_some_mut(event.window)
+        )) = egui_contexts.get_some_mut(context)
         else {
             continue;
         };
 
-        if egui_global_settings.enable_focused_non_window_context_updates {
+        if egui_global_settings.enable_focused_non_window_context_updates && bound_context.is_none()
+        {
             if let bevy_input::touch::TouchPhase::Started = event.phase {
                 if let Some(hovered_non_window_egui_context) =
                     hovered_non_window_egui_context.as_deref()
@@ -604,17 +1310,20 @@ pub fn write_window_touch_events_system(
             continue;
         }
 
-        let scale_factor = context_settings.scale_factor;
+        let scale_factor = context_settings.input_scale_factor();
         let touch_position = vec2_into_egui_pos2(event.position / scale_factor);
+        timeline.record_pointer_position(context, context_pointer_position.position);
         context_pointer_position.position = touch_position;
         write_touch_event(
-            &mut egui_input_event_writer,
+            &mut timeline,
+            arrival,
             event,
-            event.window,
+            context,
             output,
             touch_position,
             modifiers,
             &mut context_pointer_touch_id,
+            context_settings.touch_as_pointer,
         );
     }
 }
@@ -623,7 +1332,7 @@ pub fn write_window_touch_events_system(
 pub fn write_non_window_touch_events_system(
     focused_non_window_egui_context: Option<Res<FocusedNonWindowEguiContext>>,
     mut touch_input_reader: EventReader<TouchInput>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
     modifier_keys_state: Res<ModifierKeysState>,
     mut egui_contexts: Query<
         (
@@ -634,15 +1343,26 @@ pub fn write_non_window_touch_events_system(
         ),
         With<EguiContext>,
     >,
+    bound_touches: Query<&EguiBoundTouch>,
 ) {
     let modifiers = modifier_keys_state.to_egui_modifiers();
+    let mut arrivals = timeline.arrivals();
     for event in touch_input_reader.read() {
+        let arrival = timeline.arrival(&mut arrivals, *event);
         let Some(&FocusedNonWindowEguiContext(focused_non_window_egui_context)) =
             focused_non_window_egui_context.as_deref()
         else {
             continue;
         };
 
+        // Bound touches are handled by `write_window_touch_events_system`.
+        if bound_touches
+            .iter()
+            .any(|bound_touch| bound_touch.0 == event.id)
+        {
+            continue;
+        }
+
         let Some((
             context_settings,
             context_pointer_position,
@@ -661,52 +1381,118 @@ pub fn write_non_window_touch_events_system(
         }
 
         write_touch_event(
-            &mut egui_input_event_writer,
+            &mut timeline,
+            arrival,
             event,
             focused_non_window_egui_context,
             output,
             context_pointer_position.position,
             modifiers,
             &mut context_pointer_touch_id,
+            context_settings.touch_as_pointer,
         );
     }
 }
 
+/// Releases the pointer button emulated from a touch as soon as [`EguiContextSettings::touch_as_pointer`] gets
+/// disabled, so that the button doesn't stay pressed until the next touch event.
+///
+/// Is disabled together with [`write_window_touch_events_system`] (see [`crate::EguiInputSystemSettings`]).
+pub fn release_touch_pointer_system(
+    modifier_keys_state: Res<ModifierKeysState>,
+    mut timeline: ResMut<EguiInputTimeline>,
+    mut egui_contexts: Query<
+        (
+            Entity,
+            &EguiContextSettings,
+            &EguiContextPointerPosition,
+            &mut EguiContextPointerTouchId,
+        ),
+        Changed<EguiContextSettings>,
+    >,
+) {
+    let modifiers = modifier_keys_state.to_egui_modifiers();
+    for (context, context_settings, context_pointer_position, mut context_pointer_touch_id) in
+        egui_contexts.iter_mut()
+    {
+        if !context_settings
+            .input_system_settings
+            .run_write_window_touch_events_system
+        {
+            continue;
+        }
+        if context_settings.touch_as_pointer
+            || context_pointer_touch_id.pointer_touch_id.take().is_none()
+        {
+            continue;
+        }
+        timeline.send(
+            EguiInputTimeline::FRAME_START,
+            EguiInputEvent {
+                context,
+                event: egui::Event::PointerButton {
+                    pos: context_pointer_position.position,
+                    button: egui::PointerButton::Primary,
+                    pressed: false,
+                    modifiers,
+                },
+            },
+        );
+        timeline.send(
+            EguiInputTimeline::FRAME_START,
+            EguiInputEvent {
+                context,
+                event: egui::Event::PointerGone,
+            },
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn write_touch_event(
-    egui_input_event_writer: &mut EventWriter<EguiInputEvent>,
+    timeline: &mut EguiInputTimeline,
+    arrival: usize,
     event: &TouchInput,
     context: Entity,
     _output: &EguiOutput,
     pointer_position: egui::Pos2,
     modifiers: Modifiers,
     context_pointer_touch_id: &mut EguiContextPointerTouchId,
+    touch_as_pointer: bool,
 ) {
     let touch_id = egui::TouchId::from(event.id);
 
     // Emit touch event
-    egui_input_event_writer.send(EguiInputEvent {
-        context,
-        event: egui::Event::Touch {
-            device_id: egui::TouchDeviceId(event.window.to_bits()),
-            id: touch_id,
-            phase: match event.phase {
-                bevy_input::touch::TouchPhase::Started => egui::TouchPhase::Start,
-                bevy_input::touch::TouchPhase::Moved => egui::TouchPhase::Move,
-                bevy_input::touch::TouchPhase::Ended => egui::TouchPhase::End,
-                bevy_input::touch::TouchPhase::Canceled => egui::TouchPhase::Cancel,
-            },
-            pos: pointer_position,
-            force: match event.force {
-                Some(bevy_input::touch::ForceTouch::Normalized(force)) => Some(force as f32),
-                Some(bevy_input::touch::ForceTouch::Calibrated {
-                    force,
-                    max_possible_force,
-                    ..
-                }) => Some((force / max_possible_force) as f32),
-                None => None,
+    timeline.send(
+        arrival,
+        EguiInputEvent {
+            context,
+            event: egui::Event::Touch {
+                device_id: egui::TouchDeviceId(event.window.to_bits()),
+                id: touch_id,
+                phase: match event.phase {
+                    bevy_input::touch::TouchPhase::Started => egui::TouchPhase::Start,
+                    bevy_input::touch::TouchPhase::Moved => egui::TouchPhase::Move,
+                    bevy_input::touch::TouchPhase::Ended => egui::TouchPhase::End,
+                    bevy_input::touch::TouchPhase::Canceled => egui::TouchPhase::Cancel,
+                },
+                pos: pointer_position,
+                force: match event.force {
+                    Some(bevy_input::touch::ForceTouch::Normalized(force)) => Some(force as f32),
+                    Some(bevy_input::touch::ForceTouch::Calibrated {
+                        force,
+                        max_possible_force,
+                        ..
+                    }) => Some((force / max_possible_force) as f32),
+                    None => None,
+                },
             },
         },
-    });
+    );
+
+    if !touch_as_pointer {
+        return;
+    }
 
     // If we're not yet translating a touch, or we're translating this very
     // touch, …
@@ -718,42 +1504,57 @@ fn write_touch_event(
             bevy_input::touch::TouchPhase::Started => {
                 context_pointer_touch_id.pointer_touch_id = Some(event.id);
                 // First move the pointer to the right location.
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::PointerMoved(pointer_position),
-                });
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::PointerMoved(pointer_position),
+                    },
+                );
                 // Then do mouse button input.
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::PointerButton {
-                        pos: pointer_position,
-                        button: egui::PointerButton::Primary,
-                        pressed: true,
-                        modifiers,
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::PointerButton {
+                            pos: pointer_position,
+                            button: egui::PointerButton::Primary,
+                            pressed: true,
+                            modifiers,
+                        },
                     },
-                });
+                );
             }
             bevy_input::touch::TouchPhase::Moved => {
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::PointerMoved(pointer_position),
-                });
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::PointerMoved(pointer_position),
+                    },
+                );
             }
             bevy_input::touch::TouchPhase::Ended => {
                 context_pointer_touch_id.pointer_touch_id = None;
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::PointerButton {
-                        pos: pointer_position,
-                        button: egui::PointerButton::Primary,
-                        pressed: false,
-                        modifiers,
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::PointerButton {
+                            pos: pointer_position,
+                            button: egui::PointerButton::Primary,
+                            pressed: false,
+                            modifiers,
+                        },
                     },
-                });
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::PointerGone,
-                });
+                );
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::PointerGone,
+                    },
+                );
 
                 #[cfg(target_arch = "wasm32")]
                 if !is_mobile_safari() {
@@ -765,16 +1566,23 @@ fn write_touch_event(
             }
             bevy_input::touch::TouchPhase::Canceled => {
                 context_pointer_touch_id.pointer_touch_id = None;
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::PointerGone,
-                });
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::PointerGone,
+                    },
+                );
             }
         }
     }
 }
 
 /// Reads [`EguiInputEvent`] events and feeds them to Egui.
+///
+/// Also sets [`egui::RawInput::time`] and [`egui::RawInput::predicted_dt`] (for all contexts, including
+/// the ones with [`crate::EguiContextSettings::run_manually`] set). We use [`Time<Real>`] instead of [`Time<Virtual>`](bevy_time::Virtual),
+/// as UI animations aren't supposed to be affected by pausing or scaling game time.
 pub fn write_egui_input_system(
     focused_non_window_egui_context: Option<Res<FocusedNonWindowEguiContext>>,
     modifier_keys_state: Res<ModifierKeysState>,
@@ -806,5 +1614,9 @@ pub fn write_egui_input_system(
         );
         egui_input.modifiers = modifier_keys_state.to_egui_modifiers();
         egui_input.time = Some(time.elapsed_secs_f64());
+        // Egui defaults to 1/60 s, we don't want to overwrite it with zero on the first frame.
+        if time.delta_secs() > 0.0 {
+            egui_input.predicted_dt = time.delta_secs();
+        }
     }
 }
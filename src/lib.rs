@@ -14,6 +14,8 @@
 //! - Multiple windows support (see [./examples/two_windows.rs](https://github.com/vladbat00/bevy_egui/blob/v0.29.0/examples/two_windows.rs))
 //! - Paint callback support (see [./examples/paint_callback.rs](https://github.com/vladbat00/bevy_egui/blob/v0.29.0/examples/paint_callback.rs))
 //! - Mobile web virtual keyboard (still rough around the edges and only works without `prevent_default_event_handling` set to `false` in the `WindowPlugin` settings)
+//! - Forwarding of `bevy_a11y` focus and AccessKit action requests (the `accesskit` feature); note that Egui's AccessKit tree
+//!   isn't published to Bevy's AccessKit adapter yet, so assistive technologies can't see Egui widgets on their own
 //!
 //! ## Dependencies
 //!
@@ -102,6 +104,9 @@
 //!
 //! - [`bevy-inspector-egui`](https://github.com/jakobhellermann/bevy-inspector-egui)
 
+/// Clipboard management for Android.
+#[cfg(all(feature = "manage_clipboard", target_os = "android"))]
+pub mod android_clipboard;
 /// Egui render node.
 #[cfg(feature = "render")]
 pub mod egui_node;
@@ -117,6 +122,9 @@ pub mod render_systems;
 /// Mobile web keyboard input support.
 #[cfg(target_arch = "wasm32")]
 pub mod text_agent;
+/// On-screen keyboard support for desktop platforms.
+#[cfg(feature = "virtual_keyboard")]
+pub mod virtual_keyboard;
 /// Clipboard management for web.
 #[cfg(all(feature = "manage_clipboard", target_arch = "wasm32",))]
 pub mod web_clipboard;
@@ -142,7 +150,10 @@ use crate::{
 use arboard::Clipboard;
 use bevy_app::prelude::*;
 #[cfg(feature = "render")]
-use bevy_asset::{load_internal_asset, AssetEvent, Assets, Handle};
+use bevy_asset::{
+    io::Reader, load_internal_asset, Asset, AssetApp, AssetEvent, AssetId, AssetLoader, Assets,
+    Handle, LoadContext,
+};
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
     prelude::*,
@@ -154,21 +165,31 @@ use bevy_ecs::{
 use bevy_image::{Image, ImageSampler};
 use bevy_input::InputSystem;
 use bevy_log as log;
+#[cfg(feature = "render")]
+use bevy_math::Mat4;
 #[cfg(feature = "picking")]
 use bevy_picking::{
     backend::{HitData, PointerHits},
     pointer::{PointerId, PointerLocation},
 };
 use bevy_reflect::Reflect;
+#[cfg(feature = "render")]
+use bevy_reflect::TypePath;
+#[cfg(all(feature = "picking", feature = "render"))]
+use bevy_render::camera::Camera;
 #[cfg(feature = "picking")]
 use bevy_render::camera::NormalizedRenderTarget;
 #[cfg(feature = "render")]
 use bevy_render::{
+    camera::{ManualTextureViewHandle, ManualTextureViews},
     extract_component::{ExtractComponent, ExtractComponentPlugin},
     extract_resource::{ExtractResource, ExtractResourcePlugin},
-    render_resource::{LoadOp, SpecializedRenderPipelines},
+    render_resource::{BlendState, LoadOp, SpecializedRenderPipelines},
+    renderer::RenderDevice,
     ExtractSchedule, Render, RenderApp, RenderSet,
 };
+#[cfg(feature = "render")]
+use bevy_time::{Real, Time};
 use bevy_window::{PrimaryWindow, Window};
 use bevy_winit::cursor::CursorIcon;
 use output::process_output_system;
@@ -177,11 +198,86 @@ use output::process_output_system;
     not(any(target_arch = "wasm32", target_os = "android"))
 ))]
 use std::cell::{RefCell, RefMut};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
 /// Adds all Egui resources and render graph nodes.
+///
+/// ## Logic-only (headless) usage
+///
+/// Passes run and [`EguiFullOutput`], [`EguiOutput`] and [`EguiRenderOutput`] get populated without a render app,
+/// which allows testing UI logic on a headless server (ideally, with the `render` feature disabled).
+/// Contexts that don't have a render target need a [`RenderTargetSize`] to be set manually:
+///
+/// ```
+/// use bevy::{input::InputPlugin, prelude::*, window::ExitCondition};
+/// use bevy_egui::{egui, EguiContext, EguiContexts, EguiPlugin, RenderTargetSize};
+///
+/// #[derive(Resource, Default)]
+/// struct Button {
+///     rect: Option<egui::Rect>,
+///     clicked: bool,
+/// }
+///
+/// fn ui_system(mut contexts: Query<&mut EguiContext>, mut button: ResMut<Button>) {
+///     for mut ctx in contexts.iter_mut() {
+///         egui::CentralPanel::default().show(ctx.get_mut(), |ui| {
+///             let response = ui.button("Click me");
+///             button.rect = Some(response.rect);
+///             button.clicked |= response.clicked();
+///         });
+///     }
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     InputPlugin,
+///     WindowPlugin {
+///         primary_window: None,
+///         exit_condition: ExitCondition::DontExit,
+///         ..default()
+///     },
+/// ));
+/// # #[cfg(feature = "render")]
+/// # {
+/// #     app.add_plugins(bevy::asset::AssetPlugin::default());
+/// #     app.init_asset::<bevy::render::render_resource::Shader>();
+/// #     app.init_asset::<Image>();
+/// # }
+/// app.add_plugins(EguiPlugin)
+///     .init_resource::<Button>()
+///     .add_systems(Update, ui_system);
+/// let context = app
+///     .world_mut()
+///     .spawn((EguiContext::default(), RenderTargetSize::new(800.0, 600.0, 1.0)))
+///     .id();
+///
+/// // The first pass lays out the UI.
+/// app.update();
+/// let pos = app.world().resource::<Button>().rect.unwrap().center();
+///
+/// // Send a click, it gets processed during the next pass.
+/// let mut system_state = bevy::ecs::system::SystemState::<EguiContexts>::new(app.world_mut());
+/// let mut contexts = system_state.get_mut(app.world_mut());
+/// contexts.send_event(context, egui::Event::PointerMoved(pos));
+/// for pressed in [true, false] {
+///     contexts.send_event(
+///         context,
+///         egui::Event::PointerButton {
+///             pos,
+///             button: egui::PointerButton::Primary,
+///             pressed,
+///             modifiers: egui::Modifiers::NONE,
+///         },
+///     );
+/// }
+/// system_state.apply(app.world_mut());
+/// app.update();
+///
+/// assert!(app.world().resource::<Button>().clicked);
+/// ```
 pub struct EguiPlugin;
 
 /// A resource for storing global plugin settings.
@@ -194,6 +290,13 @@ pub struct EguiGlobalSettings {
     pub enable_focused_non_window_context_updates: bool,
     /// Controls running of the input systems.
     pub input_system_settings: EguiInputSystemSettings,
+    /// Set this to `true` to draw all the [`EguiRenderToImage`] contexts within a single render graph node
+    /// ([`egui_node::EguiRenderToImageBatchNode`]) instead of adding a node per context (`false` by default).
+    ///
+    /// This reduces the render graph overhead when there are many small render targets (thumbnails, etc.).
+    /// The setting is read when a context is created, so it needs to be set before spawning contexts.
+    #[cfg(feature = "render")]
+    pub batch_render_to_image_nodes: bool,
 }
 
 impl Default for EguiGlobalSettings {
@@ -201,6 +304,8 @@ impl Default for EguiGlobalSettings {
         Self {
             enable_focused_non_window_context_updates: true,
             input_system_settings: EguiInputSystemSettings::default(),
+            #[cfg(feature = "render")]
+            batch_render_to_image_nodes: false,
         }
     }
 }
@@ -212,6 +317,8 @@ pub struct EguiContextSettings {
     /// Controls if Egui is run manually.
     ///
     /// If set to `true`, a user is expected to call [`egui::Context::run`] or [`egui::Context::begin_pass`] and [`egui::Context::end_pass`] manually.
+    /// The [`begin_pass_system`] and [`end_pass_system`] systems skip such contexts entirely,
+    /// and a system running a pass manually can be added to the [`EguiManualPass`] set to get ordered correctly.
     pub run_manually: bool,
     /// Global scale factor for Egui widgets (`1.0` by default).
     ///
@@ -227,17 +334,148 @@ pub struct EguiContextSettings {
     /// }
     /// ```
     pub scale_factor: f32,
+    /// Overrides the scale used for mapping pointer and touch positions into Egui coordinates (`None` by default).
+    ///
+    /// When set, logical pointer positions get divided by this value instead of [`EguiContextSettings::scale_factor`],
+    /// while rendering still uses [`EguiContextSettings::scale_factor`]. This decouples the input mapping from
+    /// the render transform, e.g. for design-locked layouts that get letterboxed but still render at native resolution.
+    pub input_scale_override: Option<f32>,
+    /// A 4x4 color matrix applied to every pixel of the rendered Egui output (`None` by default).
+    ///
+    /// The matrix is multiplied by the premultiplied linear RGBA color in the fragment shader,
+    /// which makes it possible to implement color-blindness filters or a "night light" mode
+    /// without adding an extra render pass. `None` is equivalent to [`Mat4::IDENTITY`].
+    #[cfg(feature = "render")]
+    pub color_tint: Option<Mat4>,
+    /// Blend state used for drawing Egui meshes (`None` by default).
+    ///
+    /// Can be useful when compositing Egui over a transparent window, for which the default
+    /// premultiplied alpha blending may produce wrong results.
+    /// `None` is equivalent to [`BlendState::PREMULTIPLIED_ALPHA_BLENDING`].
+    #[cfg(feature = "render")]
+    #[reflect(ignore)]
+    pub blend_state: Option<BlendState>,
+    /// Controls whether the Egui output gets dithered to reduce banding of gradients (`true` by default,
+    /// which matches the default of `egui-wgpu`).
+    ///
+    /// An ordered dither is applied in the fragment shader, in the gamma (sRGB) space. Float (HDR) render targets
+    /// are never dithered.
+    #[cfg(feature = "render")]
+    pub dithering: bool,
+    /// Overrides the sampler of the textures managed by Egui, such as the font atlas (`None` by default).
+    ///
+    /// By default, the sampler is derived from [`egui::TextureOptions`] reported by Egui, which gives linear
+    /// filtering for the font atlas. Setting [`ImageSampler::nearest`] can make bitmap fonts crisp at integer UI scales.
+    /// User textures aren't affected. The override is applied when Egui uploads or updates a texture.
+    #[cfg(feature = "render")]
+    #[reflect(ignore)]
+    pub managed_texture_filter: Option<ImageSampler>,
+    /// If set to `true`, only the region affected by changed primitives gets redrawn (`false` by default).
+    ///
+    /// Only applies to contexts rendering to persistent targets ([`EguiRenderToImage`] and [`EguiRenderToTextureView`]).
+    /// The paint jobs are compared against the ones of the previously rendered frame, the render pass loads the
+    /// existing pixels and only the primitives intersecting the union of the changed rectangles are drawn (clipped to it).
+    /// A full redraw happens whenever the target, its size or scale factor changes, or if Egui textures were updated.
+    ///
+    /// As the dirty region isn't cleared, the previous content shines through translucent parts of the UI there
+    /// (for instance, a disappearing tooltip can leave its trace). It works best when the UI covers the target
+    /// with opaque backgrounds, e.g. with a [`egui::CentralPanel`]. Changes of user textures aren't detected.
+    #[cfg(feature = "render")]
+    pub partial_redraw: bool,
+    /// The [`LoadOp`] of the render pass drawing a window context ([`LoadOp::Load`] by default).
+    ///
+    /// The default draws the UI on top of whatever cameras rendered to the window. Set [`LoadOp::Clear`]
+    /// to clear the window to a solid color in the Egui pass itself, e.g. for UI-only windows without cameras.
+    /// Ignored for [`EguiLayer`] contexts, which are always drawn on top of their window.
+    #[cfg(feature = "render")]
+    #[reflect(ignore)]
+    pub window_load_op: LoadOp<wgpu_types::Color>,
+    /// Orders the render node of a window context relative to other window contexts (`None` by default).
+    ///
+    /// Nodes of window contexts that have an order set get executed in ascending order (window entities break ties),
+    /// while nodes without an order stay unordered relative to any other node. The value is read when the node
+    /// of the context gets created, so changing it afterwards has no effect.
+    /// Ignored for contexts that don't render to a window and for [`EguiLayer`] contexts.
+    #[cfg(feature = "render")]
+    pub pass_order: Option<i32>,
+    /// If set to `true`, all mouse wheel events are sent to Egui with the [`egui::MouseWheelUnit::Point`] unit
+    /// (`false` by default).
+    ///
+    /// Line deltas (reported by conventional mouse wheels) get converted into points using
+    /// [`egui::Options::line_scroll_speed`], which makes Egui treat mouse wheels and trackpads uniformly
+    /// (for instance, smooth scrolling is applied to small deltas regardless of the input device).
+    pub force_pixel_scroll_unit: bool,
+    /// If set to `true`, pixel deltas of mouse wheel events (reported by trackpads) get divided by the scale factor,
+    /// converting them from physical pixels into points (`false` by default).
+    ///
+    /// By default, pixel deltas are passed to Egui as points unchanged, which makes trackpads scroll faster
+    /// on high-DPI displays. Enabling the setting makes the scroll distance match the distance swiped on
+    /// the trackpad, as `egui-winit` does.
+    pub scale_pixel_scroll_deltas: bool,
+    /// If set, mouse wheel events with Ctrl (or Cmd on macOS) held are sent to Egui as [`egui::Event::Zoom`],
+    /// each wheel line multiplying the zoom by this factor (`None` by default).
+    ///
+    /// By default, Egui interprets such wheel events as zoom by itself, with a non-configurable speed.
+    /// Zoom events feed [`egui::InputState::zoom_delta`], which is used by zoomable widgets; to zoom
+    /// the whole UI, multiply [`EguiContextSettings::scale_factor`] by the delta (the Egui zoom factor
+    /// is overwritten by `bevy_egui` every frame). Trackpad pinch gestures
+    /// are translated into [`egui::Event::Zoom`] regardless of this setting.
+    pub zoom_step: Option<f32>,
+    /// Controls whether the first active touch is translated into pointer events (`true` by default).
+    ///
+    /// Egui receives [`egui::Event::Touch`] events regardless of this setting. Disabling the emulation
+    /// helps to avoid widgets receiving double input on touch devices. If the setting gets disabled while
+    /// a touch is being translated, the emulated pointer gets released right away (see [`release_touch_pointer_system`]).
+    pub touch_as_pointer: bool,
+    /// Overrides the maximum texture side that gets reported to Egui via [`egui::RawInput::max_texture_side`]
+    /// (`None` by default).
+    ///
+    /// If not set, the `max_texture_dimension_2d` limit of the render device is used (when the `render` feature
+    /// is enabled), so that Egui never allocates font atlases the GPU can't hold. Can be useful for testing
+    /// low-end GPU limits.
+    pub max_texture_side: Option<usize>,
+    /// Overrides the maximum delay (in seconds) between a release and a press for them to count as
+    /// a double click, twice this value is used for triple clicks (`None` by default).
+    ///
+    /// If not set, the value of [`EguiContextOptions`] is kept (which defaults to 0.3 seconds). Egui detects
+    /// multiple clicks by [`egui::RawInput::time`], which is set to the real elapsed time of the frame the events
+    /// are processed in, so all clicks of a frame share the same timestamp and are counted regardless of frame rate.
+    pub max_double_click_delay: Option<f64>,
     /// Is used as a default value for hyperlink [target](https://www.w3schools.com/tags/att_a_target.asp) hints.
     /// If not specified, `_self` will be used. Only matters in a web browser.
     #[cfg(feature = "open_url")]
     pub default_open_url_target: Option<String>,
+    /// If set to `true`, pasting prefers images over text (`false` by default).
+    ///
+    /// When the clipboard contains an image, [`EguiImagePasteEvent`] is sent instead of [`egui::Event::Paste`],
+    /// falling back to text otherwise. Egui widgets don't accept images, so it's up to the app to enable
+    /// this setting while its focused widget does (e.g. by comparing [`egui::Memory::focused`] with the widget id).
+    #[cfg(feature = "manage_clipboard")]
+    pub prefer_image_paste: bool,
     /// Controls if Egui should capture pointer input when using [`bevy_picking`] (i.e. suppress `bevy_picking` events when a pointer is over an Egui window).
+    ///
+    /// The [`EguiClickThrough`] marker takes precedence: contexts with it never capture the pointer.
     #[cfg(feature = "picking")]
     pub capture_pointer_input: bool,
+    /// If set to `true`, the pointer is also captured when it's over any Egui area (see [`egui::Context::is_pointer_over_area`]),
+    /// even if Egui doesn't want pointer input (`false` by default).
+    ///
+    /// Transparent areas and empty parts of panels are hovered without wanting input, so by default
+    /// `bevy_picking` events fire through them. Only matters if [`EguiContextSettings::capture_pointer_input`] is set.
+    #[cfg(feature = "picking")]
+    pub capture_pointer_input_over_area: bool,
     /// Controls running of the input systems.
     pub input_system_settings: EguiInputSystemSettings,
 }
 
+impl EguiContextSettings {
+    /// Returns the scale used for mapping pointer positions into Egui coordinates
+    /// (see [`EguiContextSettings::input_scale_override`]).
+    pub fn input_scale_factor(&self) -> f32 {
+        self.input_scale_override.unwrap_or(self.scale_factor)
+    }
+}
+
 // Just to keep the PartialEq
 impl PartialEq for EguiContextSettings {
     #[allow(clippy::let_and_return)]
@@ -254,10 +492,35 @@ impl Default for EguiContextSettings {
         Self {
             run_manually: false,
             scale_factor: 1.0,
+            input_scale_override: None,
+            #[cfg(feature = "render")]
+            color_tint: None,
+            #[cfg(feature = "render")]
+            blend_state: None,
+            #[cfg(feature = "render")]
+            dithering: true,
+            #[cfg(feature = "render")]
+            managed_texture_filter: None,
+            #[cfg(feature = "render")]
+            partial_redraw: false,
+            #[cfg(feature = "render")]
+            window_load_op: LoadOp::Load,
+            #[cfg(feature = "render")]
+            pass_order: None,
+            force_pixel_scroll_unit: false,
+            scale_pixel_scroll_deltas: false,
+            zoom_step: None,
+            touch_as_pointer: true,
+            max_texture_side: None,
+            max_double_click_delay: None,
             #[cfg(feature = "open_url")]
             default_open_url_target: None,
+            #[cfg(feature = "manage_clipboard")]
+            prefer_image_paste: false,
             #[cfg(feature = "picking")]
             capture_pointer_input: true,
+            #[cfg(feature = "picking")]
+            capture_pointer_input_over_area: false,
             input_system_settings: EguiInputSystemSettings::default(),
         }
     }
@@ -270,6 +533,8 @@ pub struct EguiInputSystemSettings {
     pub run_write_modifiers_keys_state_system: bool,
     /// Controls running of the [`write_window_pointer_moved_events_system`] system.
     pub run_write_window_pointer_moved_events_system: bool,
+    /// Controls running of the [`write_window_pointer_motion_events_system`] system.
+    pub run_write_window_pointer_motion_events_system: bool,
     /// Controls running of the [`write_pointer_button_events_system`] system.
     pub run_write_pointer_button_events_system: bool,
     /// Controls running of the [`write_window_touch_events_system`] system.
@@ -284,6 +549,14 @@ pub struct EguiInputSystemSettings {
     pub run_write_keyboard_input_events_system: bool,
     /// Controls running of the [`write_ime_events_system`] system.
     pub run_write_ime_events_system: bool,
+    /// Controls running of the [`write_window_focus_events_system`] system.
+    pub run_write_window_focus_events_system: bool,
+    /// Controls running of the [`write_accessibility_focus_events_system`] system.
+    #[cfg(feature = "accesskit")]
+    pub run_write_accessibility_focus_events_system: bool,
+    /// Controls running of the [`write_accessibility_action_requests_system`] system.
+    #[cfg(feature = "accesskit")]
+    pub run_write_accessibility_action_requests_system: bool,
     /// Controls running of the [`write_text_agent_channel_events_system`] system.
     #[cfg(target_arch = "wasm32")]
     pub run_write_text_agent_channel_events_system: bool,
@@ -297,6 +570,7 @@ impl Default for EguiInputSystemSettings {
         Self {
             run_write_modifiers_keys_state_system: true,
             run_write_window_pointer_moved_events_system: true,
+            run_write_window_pointer_motion_events_system: true,
             run_write_pointer_button_events_system: true,
             run_write_window_touch_events_system: true,
             run_write_non_window_pointer_moved_events_system: true,
@@ -304,6 +578,11 @@ impl Default for EguiInputSystemSettings {
             run_write_non_window_touch_events_system: true,
             run_write_keyboard_input_events_system: true,
             run_write_ime_events_system: true,
+            run_write_window_focus_events_system: true,
+            #[cfg(feature = "accesskit")]
+            run_write_accessibility_focus_events_system: true,
+            #[cfg(feature = "accesskit")]
+            run_write_accessibility_action_requests_system: true,
             #[cfg(target_arch = "wasm32")]
             run_write_text_agent_channel_events_system: true,
             #[cfg(all(feature = "manage_clipboard", target_arch = "wasm32"))]
@@ -325,13 +604,15 @@ pub struct EguiFullOutput(pub Option<egui::FullOutput>);
 /// A resource for accessing clipboard.
 ///
 /// The resource is available only if `manage_clipboard` feature is enabled.
-#[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
+#[cfg(feature = "manage_clipboard")]
 #[derive(Default, bevy_ecs::system::Resource)]
 pub struct EguiClipboard {
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
     clipboard: thread_local::ThreadLocal<Option<RefCell<Clipboard>>>,
     #[cfg(target_arch = "wasm32")]
     clipboard: web_clipboard::WebClipboard,
+    #[cfg(target_os = "android")]
+    clipboard: android_clipboard::AndroidClipboard,
 }
 
 /// Is used for storing Egui shapes and textures delta.
@@ -350,6 +631,13 @@ pub struct EguiRenderOutput {
     ///
     /// Value is wrapped in [`Arc`] to improve [`ExtractComponent`] performance.
     pub textures_delta: Arc<egui::TexturesDelta>,
+
+    /// Pixels per point the paint jobs were tessellated with (`0.0` if unknown).
+    ///
+    /// The paint jobs are rendered with this value rather than the current [`EguiContextSettings::scale_factor`],
+    /// so that a scale change made after a pass has begun applies with the next pass instead of rescaling
+    /// the current output.
+    pub pixels_per_point: f32,
 }
 
 impl EguiRenderOutput {
@@ -366,6 +654,270 @@ pub struct EguiOutput {
     pub platform_output: egui::PlatformOutput,
 }
 
+/// Stores whether Egui wants pointer or keyboard input, which can be used to suppress game input
+/// when the user interacts with the UI.
+///
+/// The component gets updated during the [`EguiPostUpdateSet::ProcessOutput`] system (belonging to [`PostUpdate`]).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiWantsInput {
+    /// Equals [`egui::Context::wants_pointer_input`], i.e. a pointer is over an Egui area or Egui is using it (dragging a slider, etc).
+    pub pointer: bool,
+    /// Equals [`egui::Context::wants_keyboard_input`], i.e. Egui is listening to text input (a text field has focus).
+    pub keyboard: bool,
+}
+
+/// Tessellation stats of the last Egui pass, which can be used to detect UI that produces too many shapes
+/// before it gets to the GPU.
+///
+/// The component gets updated during the [`EguiPostUpdateSet::ProcessOutput`] system (belonging to [`PostUpdate`]).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiTessellationStats {
+    /// Number of clipped shapes passed to [`egui::Context::tessellate`].
+    pub shapes: usize,
+    /// Number of primitives (meshes and paint callbacks) produced by the tessellator.
+    pub primitives: usize,
+    /// Total number of vertices of the tessellated meshes.
+    pub vertices: usize,
+    /// Total number of indices of the tessellated meshes.
+    pub indices: usize,
+}
+
+/// Stores whether the output of the last Egui pass needs to be redrawn, i.e. Egui requested a repaint
+/// and the output isn't empty (see [`EguiRenderOutput::is_empty`]).
+///
+/// It's a cheap way to check from the main world whether a redraw should be scheduled at all, e.g. to skip
+/// presenting frames on idle (which is also what `bevy_egui` does when it decides to send [`RequestRedraw`](bevy_window::RequestRedraw)).
+/// The component gets updated during the [`EguiPostUpdateSet::ProcessOutput`] system (belonging to [`PostUpdate`]).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EguiNeedsRedraw(pub bool);
+
+/// Is sent by [`process_output_system`] for every context Egui requested a repaint for during the last pass
+/// (due to animations, hovered widgets, input, or [`egui::Context::request_repaint_after`] calls).
+///
+/// Unlike [`EguiNeedsRedraw`], the event is sent even if the output is empty and carries the requested
+/// repaint delay, which makes it suitable for driving custom present schedulers. No event is sent for a context
+/// that is idle (i.e. its repaint delay is [`Duration::MAX`]).
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EguiRepaintRequested {
+    /// The context entity.
+    pub entity: Entity,
+    /// The delay after which Egui wants to be repainted ([`Duration::ZERO`] means as soon as possible).
+    pub delay: Duration,
+}
+
+/// Stores whether a context skips its pass during the current frame, which happens while its render target is too small
+/// to draw into (e.g. a window is minimized), or to respect [`EguiRenderToImage::update_interval`].
+///
+/// When a pass is skipped, the last paint jobs and textures are kept, so a render target keeps showing
+/// the last rendered UI. Systems that draw UI into throttled contexts should check [`EguiPassThrottle::skip_pass`]
+/// and skip drawing as well, as there's no active Egui pass to draw into.
+#[derive(Component, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "render", derive(ExtractComponent))]
+pub struct EguiPassThrottle {
+    /// Whether the pass is skipped during the current frame.
+    pub skip_pass: bool,
+    /// Elapsed [`Time<Real>`](bevy_time::Real) of the last pass. Set it to `None` to force a pass on the next frame.
+    pub last_pass: Option<Duration>,
+}
+
+/// Egui style applied to a context by the plugin.
+///
+/// Insert this component to a context entity to set its [`egui::Style`]. The style gets applied during
+/// [`EguiPreUpdateSet::InitContexts`] (or [`EguiStartupSet::InitContexts`]) and is re-applied every time the component
+/// is changed, so switching between dark and light modes is as easy as mutating [`egui::Style::visuals`].
+#[derive(Component, Clone, Debug, Default, Deref, DerefMut)]
+pub struct EguiContextStyle(pub egui::Style);
+
+/// Egui options applied to a context by the plugin.
+///
+/// Insert this component to a context entity to set its [`egui::Options`] (such as tessellation options,
+/// `zoom_with_keyboard` or `line_scroll_speed`). Similarly to [`EguiContextStyle`], the options get applied during
+/// [`EguiPreUpdateSet::InitContexts`] (or [`EguiStartupSet::InitContexts`]) and are re-applied every time the
+/// component is changed. For example, disabling [`egui::epaint::TessellationOptions::feathering`] makes the UI crisp
+/// for pixel-art games.
+///
+/// Note that [`egui::Options`] also contain styles, [`EguiContextStyle`] is applied after the options (and
+/// re-applied whenever they change). [`egui::Options::zoom_factor`] is ignored, use
+/// [`EguiContextSettings::scale_factor`] instead.
+#[derive(Component, Clone, Debug, Default, Deref, DerefMut)]
+pub struct EguiContextOptions(pub egui::Options);
+
+/// Callback initializing every new Egui context, e.g. to set up fonts, style, options or restore memory.
+///
+/// Insert the resource when setting up the app to configure all contexts the same way, including the ones created
+/// at runtime (such as contexts of windows spawned mid-game or render-to-image contexts). The callback is called
+/// exactly once per context by [`init_new_contexts_observer`], right after the [`EguiContext`] component is
+/// inserted. [`EguiContextOptions`] and [`EguiContextStyle`] are applied later, so they take precedence.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_egui::{egui, EguiContextInit, EguiPlugin};
+///
+/// App::new()
+///     .add_plugins((DefaultPlugins, EguiPlugin))
+///     .insert_resource(EguiContextInit::new(|ctx| {
+///         ctx.set_visuals(egui::Visuals::light());
+///     }))
+///     .run();
+/// ```
+#[derive(Resource)]
+pub struct EguiContextInit(pub Box<dyn Fn(&egui::Context) + Send + Sync>);
+
+impl EguiContextInit {
+    /// Creates the resource from a callback.
+    pub fn new(init: impl Fn(&egui::Context) + Send + Sync + 'static) -> Self {
+        Self(Box::new(init))
+    }
+}
+
+/// A font file (TrueType or OpenType) loaded as an asset to be used by Egui.
+///
+/// [`EguiFontLoader`] isn't registered for any file extension (so it doesn't take over the fonts of `bevy_text`),
+/// fonts have to be loaded with the type specified: `asset_server.load::<EguiFont>("fonts/FiraMono-Medium.ttf")`.
+/// Inserting a handle into [`EguiFontMap`] lets the type get inferred.
+#[cfg(feature = "render")]
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct EguiFont {
+    /// Raw bytes of the font file.
+    pub data: Vec<u8>,
+}
+
+/// Loads [`EguiFont`] assets from `ttf` and `otf` files, used for typed loads only (see [`EguiFont`]).
+#[cfg(feature = "render")]
+#[derive(Default)]
+pub struct EguiFontLoader;
+
+#[cfg(feature = "render")]
+impl AssetLoader for EguiFontLoader {
+    type Asset = EguiFont;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<EguiFont, Self::Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+        Ok(EguiFont { data })
+    }
+}
+
+/// Fonts applied to a context by the plugin, per [`egui::FontFamily`].
+///
+/// Insert this component to a context entity to use fonts from [`EguiFont`] assets. Each font takes precedence
+/// over the fonts [`egui::FontDefinitions::default`] has for its family (which are still used as a fallback
+/// for missing glyphs), custom families ([`egui::FontFamily::Name`]) get created.
+///
+/// The fonts get applied during [`EguiPreUpdateSet::InitContexts`] once all of them are loaded (the previous
+/// fonts are kept until then), and are re-applied every time the component is changed or any of the fonts
+/// is reloaded (enable the `file_watcher` feature of Bevy for hot-reloading).
+/// Removing the component restores the default fonts.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_egui::{egui, EguiFontMap};
+///
+/// fn setup_fonts(mut commands: Commands, asset_server: Res<AssetServer>, window: Single<Entity, With<Window>>) {
+///     let mut font_map = EguiFontMap::default();
+///     font_map.insert(
+///         egui::FontFamily::Monospace,
+///         asset_server.load("fonts/FiraMono-Medium.ttf"),
+///     );
+///     commands.entity(*window).insert(font_map);
+/// }
+/// ```
+#[cfg(feature = "render")]
+#[derive(Component, Clone, Debug, Default, Deref, DerefMut)]
+pub struct EguiFontMap(pub bevy_utils::HashMap<egui::FontFamily, Handle<EguiFont>>);
+
+#[cfg(feature = "render")]
+impl EguiFontMap {
+    /// Builds font definitions out of the loaded fonts, returns `None` if any of the fonts isn't loaded yet.
+    pub fn font_definitions(&self, fonts: &Assets<EguiFont>) -> Option<egui::FontDefinitions> {
+        let mut definitions = egui::FontDefinitions::default();
+        for (family, handle) in self.iter() {
+            let font = fonts.get(handle)?;
+            let name = format!("bevy_egui_{}", handle.id());
+            definitions.font_data.insert(
+                name.clone(),
+                Arc::new(egui::FontData::from_owned(font.data.clone())),
+            );
+            definitions
+                .families
+                .entry(family.clone())
+                .or_default()
+                .insert(0, name);
+        }
+        Some(definitions)
+    }
+}
+
+/// Blend states of individual Egui layers (identified by [`egui::LayerId`], not to be confused with [`EguiLayer`]).
+///
+/// Insert this component to a context entity to render the shapes of the listed layers with a custom blend state,
+/// while the rest of the UI is rendered with [`EguiContextSettings::blend_state`]. For example, an area rendered with
+/// [`BlendState`] using additive color components can add glow effects on top of the scene.
+///
+/// ```no_run
+/// use bevy::{prelude::*, render::render_resource::{BlendComponent, BlendFactor, BlendOperation, BlendState}};
+/// use bevy_egui::{egui, EguiLayerBlendStates};
+///
+/// fn additive_glow_area(mut commands: Commands, window: Single<Entity, With<Window>>) {
+///     let additive = BlendComponent {
+///         src_factor: BlendFactor::One,
+///         dst_factor: BlendFactor::One,
+///         operation: BlendOperation::Add,
+///     };
+///     let mut blend_states = EguiLayerBlendStates::default();
+///     blend_states.insert(
+///         egui::LayerId::new(egui::Order::Foreground, egui::Id::new("glow")),
+///         BlendState { color: additive, alpha: additive },
+///     );
+///     commands.entity(*window).insert(blend_states);
+/// }
+/// ```
+///
+/// Blend states are applied by [`end_pass_system`], make sure to call [`EguiLayerBlendStates::apply`]
+/// before [`egui::Context::end_pass`] if [`EguiContextSettings::run_manually`] is set to `true`.
+#[cfg(feature = "render")]
+#[derive(Component, Clone, Debug, Default, Deref, DerefMut, ExtractComponent)]
+pub struct EguiLayerBlendStates(pub bevy_utils::HashMap<egui::LayerId, BlendState>);
+
+#[cfg(feature = "render")]
+impl EguiLayerBlendStates {
+    /// Marks the shapes painted to the listed layers, so that the render node can switch their blend state.
+    pub fn apply(&self, ctx: &egui::Context) {
+        let screen_rect = ctx.screen_rect();
+        ctx.graphics_mut(|graphics| {
+            for (layer_id, blend_state) in self.iter() {
+                let Some(paint_list) = graphics.get_mut(*layer_id) else {
+                    continue;
+                };
+                if paint_list.is_empty() {
+                    continue;
+                }
+                let marker = |blend_state| {
+                    egui::Shape::Callback(egui::PaintCallback {
+                        rect: screen_rect,
+                        callback: Arc::new(egui_node::EguiBlendStateMarker(blend_state)),
+                    })
+                };
+                // The list is rebuilt to prepend the marker as a separate shape: if it was merged into the first shape,
+                // it would inherit its clip rect and get dropped together with it if the rect is empty.
+                let shapes = std::mem::take(paint_list);
+                paint_list.add(screen_rect, marker(Some(*blend_state)));
+                for clipped_shape in shapes.all_entries() {
+                    paint_list.add(clipped_shape.clip_rect, clipped_shape.shape.clone());
+                }
+                paint_list.add(screen_rect, marker(None));
+            }
+        });
+    }
+}
+
 /// A component for storing `bevy_egui` context.
 #[derive(Clone, Component, Default)]
 #[cfg_attr(feature = "render", derive(ExtractComponent))]
@@ -378,6 +930,10 @@ pub struct EguiOutput {
     EguiFullOutput,
     EguiRenderOutput,
     EguiOutput,
+    EguiWantsInput,
+    EguiTessellationStats,
+    EguiNeedsRedraw,
+    EguiPassThrottle,
     RenderTargetSize,
     CursorIcon
 )]
@@ -414,17 +970,124 @@ impl EguiContext {
     pub fn get_mut(&mut self) -> &mut egui::Context {
         &mut self.ctx
     }
+
+    /// Returns `true` if the pointer is over an Egui area (equals [`egui::Context::is_pointer_over_area`]).
+    ///
+    /// Doesn't depend on the `render` feature, so it can be used to gate input in logic-only builds.
+    #[must_use]
+    pub fn is_pointer_over_area(&self) -> bool {
+        self.ctx.is_pointer_over_area()
+    }
+
+    /// Returns the latest pointer position Egui has received, in logical points (i.e. with the scale factor applied).
+    ///
+    /// Equals [`egui::Context::pointer_latest_pos`], which is `None` if the pointer has left the context (or has never
+    /// entered it). See also [`EguiContexts::pointer_pos_for_entity`].
+    #[must_use]
+    pub fn pointer_pos(&self) -> Option<egui::Pos2> {
+        self.ctx.pointer_latest_pos()
+    }
+
+    /// Returns `true` if a point (in logical points) is over an Egui area.
+    ///
+    /// Uses the same hit test as [`egui::Context::is_pointer_over_area`]: points over windows and other areas
+    /// are always contained, points over the background layer are contained only if they are covered by panels.
+    #[must_use]
+    pub fn contains_pointer(&self, pos: egui::Pos2) -> bool {
+        match self.ctx.layer_id_at(pos) {
+            Some(layer_id) if layer_id.order == egui::Order::Background => {
+                !self.ctx.available_rect().contains(pos)
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+}
+
+/// A read-only query that provides access to the state of Egui contexts, which can be useful for
+/// building third-party tools (inspectors, profilers, etc.) that process all contexts in a single system.
+///
+/// The query doesn't include [`EguiContext`] itself, so it can be used in the same system as [`EguiContexts`].
+///
+/// ```rust
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_egui::EguiContextReadQuery;
+/// fn inspect_contexts_system(contexts: Query<EguiContextReadQuery>) {
+///     for context in contexts.iter() {
+///         println!(
+///             "{:?}: {} events, wants pointer input: {}",
+///             context.entity,
+///             context.input.events.len(),
+///             context.wants_input.pointer
+///         );
+///     }
+/// }
+/// ```
+#[derive(QueryData)]
+pub struct EguiContextReadQuery {
+    /// Entity of a context.
+    pub entity: Entity,
+    /// Context settings.
+    pub settings: &'static EguiContextSettings,
+    /// Input that gets fed to Egui on the next pass.
+    pub input: &'static EguiInput,
+    /// Last Egui output.
+    pub output: &'static EguiOutput,
+    /// Last paint jobs and textures delta.
+    pub render_output: &'static EguiRenderOutput,
+    /// Whether Egui wants pointer or keyboard input.
+    pub wants_input: &'static EguiWantsInput,
+    /// Tessellation stats of the last pass.
+    pub tessellation_stats: &'static EguiTessellationStats,
+    /// Whether the last output needs to be redrawn.
+    pub needs_redraw: &'static EguiNeedsRedraw,
+    /// Size of the render target.
+    pub render_target_size: &'static RenderTargetSize,
+    /// Pointer position, in logical points.
+    pub pointer_position: &'static EguiContextPointerPosition,
 }
 
 #[cfg(not(feature = "render"))]
 type EguiContextsFilter = With<Window>;
 
 #[cfg(feature = "render")]
-type EguiContextsFilter = Or<(With<Window>, With<EguiRenderToImage>)>;
+type EguiContextsFilter = Or<(
+    With<Window>,
+    With<EguiRenderToImage>,
+    With<EguiRenderToTextureView>,
+    With<EguiLayer>,
+)>;
+
+/// Marks the context that [`EguiContexts::ctx_mut`] (and the other primary context getters) return instead of
+/// the primary window context.
+///
+/// Useful when the main UI lives in a secondary window (or renders to an image). If there are several marked
+/// contexts, any of them may be returned. Without the marker, the context of the [`PrimaryWindow`] is used.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct EguiPrimaryContext;
 
 #[derive(SystemParam)]
 /// A helper SystemParam that provides a way to get [`EguiContext`] with less boilerplate and
 /// combines a proxy interface to the [`EguiUserTextures`] resource.
+///
+/// The panicking getters (such as [`EguiContexts::ctx_mut`]) expect a context to be initialized. If a system may
+/// run before it exists (e.g. when the primary window is created lazily), use the fallible variants and return early.
+/// The texture methods don't depend on any context and can be used before one is created.
+///
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_egui::{egui, EguiContexts};
+/// fn ui_system(mut contexts: EguiContexts, mut image: Local<Option<egui::TextureId>>, asset_server: Res<AssetServer>) {
+///     if image.is_none() {
+///         *image = contexts.try_add_image(asset_server.load("icon.png"));
+///     }
+///     let Some(ctx) = contexts.try_ctx_mut() else {
+///         // The primary window context hasn't been created yet.
+///         return;
+///     };
+///     egui::Window::new("Hello").show(ctx, |ui| ui.label("world"));
+/// }
+/// ```
 pub struct EguiContexts<'w, 's> {
     q: Query<
         'w,
@@ -432,16 +1095,19 @@ pub struct EguiContexts<'w, 's> {
         (
             Entity,
             &'static mut EguiContext,
+            &'static EguiContextPointerPosition,
             Option<&'static PrimaryWindow>,
+            Has<EguiPrimaryContext>,
         ),
         EguiContextsFilter,
     >,
     #[cfg(feature = "render")]
     user_textures: ResMut<'w, EguiUserTextures>,
+    input_events: EventWriter<'w, EguiInputEvent>,
 }
 
 impl EguiContexts<'_, '_> {
-    /// Egui context of the primary window.
+    /// Egui context of the primary window (or the context marked with [`EguiPrimaryContext`]).
     #[must_use]
     pub fn ctx_mut(&mut self) -> &mut egui::Context {
         self.try_ctx_mut()
@@ -451,15 +1117,17 @@ impl EguiContexts<'_, '_> {
     /// Fallible variant of [`EguiContexts::ctx_mut`].
     #[must_use]
     pub fn try_ctx_mut(&mut self) -> Option<&mut egui::Context> {
-        self.q
-            .iter_mut()
-            .find_map(|(_window_entity, ctx, primary_window)| {
-                if primary_window.is_some() {
-                    Some(ctx.into_inner().get_mut())
-                } else {
-                    None
-                }
-            })
+        let mut primary_window_ctx = None;
+        for (_entity, ctx, _pointer_position, primary_window, primary_context) in self.q.iter_mut()
+        {
+            if primary_context {
+                return Some(ctx.into_inner().get_mut());
+            }
+            if primary_window.is_some() {
+                primary_window_ctx = Some(ctx);
+            }
+        }
+        primary_window_ctx.map(|ctx| ctx.into_inner().get_mut())
     }
 
     /// Egui context of a specific entity.
@@ -473,15 +1141,15 @@ impl EguiContexts<'_, '_> {
     #[must_use]
     #[track_caller]
     pub fn try_ctx_for_entity_mut(&mut self, entity: Entity) -> Option<&mut egui::Context> {
-        self.q
-            .iter_mut()
-            .find_map(|(window_entity, ctx, _primary_window)| {
+        self.q.iter_mut().find_map(
+            |(window_entity, ctx, _pointer_position, _primary_window, _primary_context)| {
                 if window_entity == entity {
                     Some(ctx.into_inner().get_mut())
                 } else {
                     None
                 }
-            })
+            },
+        )
     }
 
     /// Allows to get multiple contexts at the same time. This function is useful when you want
@@ -491,12 +1159,35 @@ impl EguiContexts<'_, '_> {
         &mut self,
         ids: [Entity; N],
     ) -> Result<[&mut egui::Context; N], QueryEntityError> {
+        self.q.get_many_mut(ids).map(|arr| {
+            arr.map(
+                |(_window_entity, ctx, _pointer_position, _primary_window, _primary_context)| {
+                    ctx.into_inner().get_mut()
+                },
+            )
+        })
+    }
+
+    /// Returns the entities of all the contexts (including the ones rendering to images).
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
         self.q
-            .get_many_mut(ids)
-            .map(|arr| arr.map(|(_window_entity, ctx, _primary_window)| ctx.into_inner().get_mut()))
+            .iter()
+            .map(|(entity, _ctx, _pointer_position, _primary_window, _primary_context)| entity)
+    }
+
+    /// Runs a closure against every context (including the ones rendering to images).
+    ///
+    /// The closure receives a context entity, a flag that indicates whether the context belongs to the primary window,
+    /// and the context itself. This is useful for drawing the same UI (such as overlays) in every window.
+    pub fn for_each_context(&mut self, mut f: impl FnMut(Entity, bool, &mut egui::Context)) {
+        for (entity, mut ctx, _pointer_position, primary_window, _primary_context) in
+            self.q.iter_mut()
+        {
+            f(entity, primary_window.is_some(), ctx.get_mut());
+        }
     }
 
-    /// Egui context of the primary window.
+    /// Egui context of the primary window (or the context marked with [`EguiPrimaryContext`]).
     ///
     /// Even though the mutable borrow isn't necessary, as the context is wrapped into `RwLock`,
     /// using the immutable getter is gated with the `immutable_ctx` feature. Using the immutable
@@ -524,15 +1215,16 @@ impl EguiContexts<'_, '_> {
     #[cfg(feature = "immutable_ctx")]
     #[must_use]
     pub fn try_ctx(&self) -> Option<&egui::Context> {
-        self.q
-            .iter()
-            .find_map(|(_window_entity, ctx, primary_window)| {
-                if primary_window.is_some() {
-                    Some(ctx.get())
-                } else {
-                    None
-                }
-            })
+        let mut primary_window_ctx = None;
+        for (_entity, ctx, _pointer_position, primary_window, primary_context) in self.q.iter() {
+            if primary_context {
+                return Some(ctx.get());
+            }
+            if primary_window.is_some() {
+                primary_window_ctx = Some(ctx);
+            }
+        }
+        primary_window_ctx.map(EguiContext::get)
     }
 
     /// Egui context of a specific window.
@@ -564,15 +1256,82 @@ impl EguiContexts<'_, '_> {
     #[track_caller]
     #[cfg(feature = "immutable_ctx")]
     pub fn try_ctx_for_entity(&self, entity: Entity) -> Option<&egui::Context> {
-        self.q
-            .iter()
-            .find_map(|(window_entity, ctx, _primary_window)| {
+        self.q.iter().find_map(
+            |(window_entity, ctx, _pointer_position, _primary_window, _primary_context)| {
                 if window_entity == entity {
                     Some(ctx.get())
                 } else {
                     None
                 }
-            })
+            },
+        )
+    }
+
+    /// Sends an Egui event to a specific context.
+    ///
+    /// This is the way to drive contexts that don't receive pointer input from Bevy, such as [`EguiRenderToImage`]
+    /// contexts (for example, to click buttons programmatically in world-space panels or in automated tests).
+    /// Events are fed to Egui during [`EguiInputSet::WriteEguiEvents`], events sent after this set are processed
+    /// on the next frame.
+    pub fn send_event(&mut self, entity: Entity, event: egui::Event) {
+        self.input_events.send(EguiInputEvent {
+            context: entity,
+            event,
+        });
+    }
+
+    /// Returns `true` if the pointer is over an Egui area of a specific context (see [`EguiContext::is_pointer_over_area`]).
+    ///
+    /// Returns `false` if the context doesn't exist.
+    #[must_use]
+    pub fn is_pointer_over_area_for_entity(&self, entity: Entity) -> bool {
+        self.q.get(entity).is_ok_and(
+            |(_window_entity, ctx, _pointer_position, _primary_window, _primary_context)| {
+                ctx.is_pointer_over_area()
+            },
+        )
+    }
+
+    /// Returns `true` if a point (in logical points) is over an Egui area of a specific context
+    /// (see [`EguiContext::contains_pointer`]).
+    ///
+    /// Returns `false` if the context doesn't exist.
+    #[must_use]
+    pub fn contains_pointer_for_entity(&self, entity: Entity, pos: egui::Pos2) -> bool {
+        self.q.get(entity).is_ok_and(
+            |(_window_entity, ctx, _pointer_position, _primary_window, _primary_context)| {
+                ctx.contains_pointer(pos)
+            },
+        )
+    }
+
+    /// Egui pointer position of the primary window context (or the context marked with [`EguiPrimaryContext`]),
+    /// in logical points (i.e. with the scale factor applied).
+    ///
+    /// The position is the same as the one that gets fed to Egui, so it can be used to place UI
+    /// relative to the pointer consistently with Egui.
+    #[must_use]
+    pub fn pointer_pos(&self) -> Option<egui::Pos2> {
+        let mut primary_window_pointer_pos = None;
+        for (_entity, _ctx, pointer_position, primary_window, primary_context) in self.q.iter() {
+            if primary_context {
+                return Some(pointer_position.position);
+            }
+            if primary_window.is_some() {
+                primary_window_pointer_pos = Some(pointer_position.position);
+            }
+        }
+        primary_window_pointer_pos
+    }
+
+    /// Egui pointer position of a specific context, in logical points (i.e. with the scale factor applied).
+    #[must_use]
+    pub fn pointer_pos_for_entity(&self, entity: Entity) -> Option<egui::Pos2> {
+        self.q.get(entity).ok().map(
+            |(_window_entity, _ctx, pointer_position, _primary_window, _primary_context)| {
+                pointer_position.position
+            },
+        )
     }
 
     /// Can accept either a strong or a weak handle.
@@ -588,6 +1347,37 @@ impl EguiContexts<'_, '_> {
         self.user_textures.add_image(image)
     }
 
+    /// Same as [`EguiContexts::add_image`], but also sets a color conversion applied when the texture is sampled
+    /// (see [`EguiUserTextures::add_image_with_color_conversion`]).
+    #[cfg(feature = "render")]
+    pub fn add_image_with_color_conversion(
+        &mut self,
+        image: Handle<Image>,
+        color_conversion: TextureColorConversion,
+    ) -> egui::TextureId {
+        self.user_textures
+            .add_image_with_color_conversion(image, color_conversion)
+    }
+
+    /// Fallible variant of [`EguiContexts::add_image`], returns [`None`] if there are no texture ids left
+    /// (see [`EguiUserTextures::try_add_image`]).
+    #[cfg(feature = "render")]
+    pub fn try_add_image(&mut self, image: Handle<Image>) -> Option<egui::TextureId> {
+        self.user_textures.try_add_image(image)
+    }
+
+    /// Fallible variant of [`EguiContexts::add_image_with_color_conversion`], returns [`None`] if there are
+    /// no texture ids left.
+    #[cfg(feature = "render")]
+    pub fn try_add_image_with_color_conversion(
+        &mut self,
+        image: Handle<Image>,
+        color_conversion: TextureColorConversion,
+    ) -> Option<egui::TextureId> {
+        self.user_textures
+            .try_add_image_with_color_conversion(image, color_conversion)
+    }
+
     /// Removes the image handle and an Egui texture id associated with it.
     #[cfg(feature = "render")]
     #[track_caller]
@@ -604,10 +1394,85 @@ impl EguiContexts<'_, '_> {
     }
 }
 
+/// A read-only SystemParam that provides access to the last [`EguiOutput`] of contexts, mirroring [`EguiContexts`].
+///
+/// As it doesn't access [`EguiContext`], it doesn't conflict with systems that use contexts mutably.
+///
+/// ```rust
+/// # use bevy_egui::EguiOutputs;
+/// fn ime_state_system(outputs: EguiOutputs) {
+///     if let Some(platform_output) = outputs.try_primary_platform_output() {
+///         println!("IME is active: {}", platform_output.ime.is_some());
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct EguiOutputs<'w, 's> {
+    q: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static EguiOutput,
+            Option<&'static PrimaryWindow>,
+            Has<EguiPrimaryContext>,
+        ),
+        EguiContextsFilter,
+    >,
+}
+
+impl EguiOutputs<'_, '_> {
+    /// Platform output of the primary window context (or the context marked with [`EguiPrimaryContext`]).
+    #[must_use]
+    pub fn primary_platform_output(&self) -> &egui::PlatformOutput {
+        self.try_primary_platform_output()
+            .expect("`EguiOutputs::primary_platform_output` was called for an uninitialized context (primary window)")
+    }
+
+    /// Fallible variant of [`EguiOutputs::primary_platform_output`].
+    #[must_use]
+    pub fn try_primary_platform_output(&self) -> Option<&egui::PlatformOutput> {
+        let mut primary_window_output = None;
+        for (_entity, output, primary_window, primary_context) in self.q.iter() {
+            if primary_context {
+                return Some(&output.platform_output);
+            }
+            if primary_window.is_some() {
+                primary_window_output = Some(&output.platform_output);
+            }
+        }
+        primary_window_output
+    }
+
+    /// Platform output of a specific context.
+    #[must_use]
+    #[track_caller]
+    pub fn platform_output_for(&self, entity: Entity) -> &egui::PlatformOutput {
+        self.try_platform_output_for(entity)
+            .unwrap_or_else(|| panic!("`EguiOutputs::platform_output_for` was called for an uninitialized context (entity {entity:?})"))
+    }
+
+    /// Fallible variant of [`EguiOutputs::platform_output_for`].
+    #[must_use]
+    pub fn try_platform_output_for(&self, entity: Entity) -> Option<&egui::PlatformOutput> {
+        self.q
+            .get(entity)
+            .ok()
+            .map(|(_entity, output, _primary_window, _primary_context)| &output.platform_output)
+    }
+}
+
 /// Contexts with this component will render UI to a specified image.
 ///
 /// You can create an entity just with this component, `bevy_egui` will initialize an [`EguiContext`]
 /// automatically.
+///
+/// Such contexts receive input only when focused or hovered (see [`HoveredNonWindowEguiContext`] and
+/// [`FocusedNonWindowEguiContext`]). To drive them otherwise, send events with [`EguiContexts::send_event`].
+///
+/// Contexts aren't tied to the lifetime of cameras or meshes displaying the image: despawn the context entity
+/// (or remove the component) when it's no longer needed, which tears down its render node and frees its textures.
+/// [`EguiContexts::entities`] can be used to find the contexts to despawn.
 #[cfg(feature = "render")]
 #[derive(Component, Clone, Debug, ExtractComponent)]
 #[require(EguiContext)]
@@ -619,39 +1484,315 @@ pub struct EguiRenderToImage {
     /// You'll likely want [`LoadOp::Clear`], unless you need to draw the UI on top of existing
     /// pixels of the image.
     pub load_op: LoadOp<wgpu_types::Color>,
+    /// If set to `true`, [`EguiRenderToImage::load_op`] is used only for the first rendered frame
+    /// (and when the image handle changes), the following frames are rendered with [`LoadOp::Load`]
+    /// (`false` by default).
+    ///
+    /// This allows drawing onto a persistent offscreen canvas that accumulates the output across frames.
+    pub clear_once: bool,
+    /// Minimum interval between Egui passes of this context (`None` by default, meaning a pass runs every frame).
+    ///
+    /// Can be used to save CPU and GPU time for contexts that don't need to be updated every frame,
+    /// such as thumbnails. See [`EguiPassThrottle`] for more details.
+    pub update_interval: Option<Duration>,
+    /// If set to `true`, the image is read back to the CPU every frame and its pixels are stored
+    /// in the [`EguiRenderToImageFrame`] component of the context entity (`false` by default).
+    ///
+    /// Can be used to stream the UI (e.g. as a video). The image must be created with
+    /// [`TextureUsages::COPY_SRC`](bevy_render::render_resource::TextureUsages::COPY_SRC).
+    /// The readback is asynchronous and reuses a pool of staging buffers, so it doesn't stall rendering,
+    /// but frames are delivered with a latency of at least one frame.
+    pub readback: bool,
 }
 
 #[cfg(feature = "render")]
 impl EguiRenderToImage {
-    /// Creates a component from an image handle and sets [`EguiRenderToImage::load_op`] to [`LoadOp::Clear].
+    /// Creates a component from an image handle and sets [`EguiRenderToImage::load_op`] to [`LoadOp::Clear`].
     pub fn new(handle: Handle<Image>) -> Self {
         Self {
             handle,
             load_op: LoadOp::Clear(wgpu_types::Color::TRANSPARENT),
+            clear_once: false,
+            update_interval: None,
+            readback: false,
         }
     }
-}
-
-/// A resource for storing `bevy_egui` user textures.
-#[derive(Clone, bevy_ecs::system::Resource, ExtractResource)]
-#[cfg(feature = "render")]
-pub struct EguiUserTextures {
-    textures: bevy_utils::HashMap<Handle<Image>, u64>,
-    free_list: Vec<u64>,
-}
 
-#[cfg(feature = "render")]
-impl Default for EguiUserTextures {
-    fn default() -> Self {
+    /// Creates a component from an image handle and sets [`EguiRenderToImage::load_op`] to [`LoadOp::Clear`]
+    /// with the specified color.
+    pub fn with_clear_color(handle: Handle<Image>, color: impl Into<wgpu_types::Color>) -> Self {
         Self {
-            textures: bevy_utils::HashMap::new(),
-            free_list: vec![0],
+            load_op: LoadOp::Clear(color.into()),
+            ..Self::new(handle)
+        }
+    }
+
+    /// Sets [`EguiRenderToImage::clear_once`].
+    #[must_use]
+    pub fn with_clear_once(self, clear_once: bool) -> Self {
+        Self { clear_once, ..self }
+    }
+
+    /// Sets [`EguiRenderToImage::update_interval`].
+    #[must_use]
+    pub fn with_update_interval(self, update_interval: Option<Duration>) -> Self {
+        Self {
+            update_interval,
+            ..self
+        }
+    }
+
+    /// Sets [`EguiRenderToImage::readback`].
+    #[must_use]
+    pub fn with_readback(self, readback: bool) -> Self {
+        Self { readback, ..self }
+    }
+}
+
+/// The last frame of an [`EguiRenderToImage`] context read back to the CPU (see [`EguiRenderToImage::readback`]).
+///
+/// Gets inserted by `bevy_egui` and updated every time a readback completes, which happens asynchronously,
+/// so the pixels lag behind the rendered UI by at least one frame.
+#[cfg(feature = "render")]
+#[derive(Component, Clone, Debug, Default)]
+pub struct EguiRenderToImageFrame {
+    /// Size of the image, in pixels.
+    pub size: bevy_math::UVec2,
+    /// Tightly packed pixels (rows are stored without padding) in the format of the image,
+    /// e.g. RGBA for [`TextureFormat::Rgba8UnormSrgb`](bevy_render::render_resource::TextureFormat::Rgba8UnormSrgb).
+    pub data: Vec<u8>,
+}
+
+/// Contexts with this component will render UI to a texture view registered in [`ManualTextureViews`].
+///
+/// This allows rendering Egui into textures created outside of Bevy's asset system (e.g. with raw `wgpu`),
+/// without an [`Assets<Image>`] round-trip. You can create an entity just with this component, `bevy_egui`
+/// will initialize an [`EguiContext`] automatically.
+///
+/// The size of the render target is taken from [`ManualTextureView::size`](bevy_render::camera::ManualTextureView::size).
+/// Such contexts receive input the same way as [`EguiRenderToImage`] contexts.
+#[cfg(feature = "render")]
+#[derive(Component, Clone, Debug, ExtractComponent)]
+#[require(EguiContext)]
+pub struct EguiRenderToTextureView {
+    /// A handle of a texture view to render to.
+    pub handle: ManualTextureViewHandle,
+    /// Customizable [`LoadOp`] for the render node which will be created for this context.
+    pub load_op: LoadOp<wgpu_types::Color>,
+}
+
+#[cfg(feature = "render")]
+impl EguiRenderToTextureView {
+    /// Creates a component from a texture view handle and sets [`EguiRenderToTextureView::load_op`] to [`LoadOp::Clear`].
+    pub fn new(handle: ManualTextureViewHandle) -> Self {
+        Self {
+            handle,
+            load_op: LoadOp::Clear(wgpu_types::Color::TRANSPARENT),
         }
     }
 }
 
+/// Contexts with this component will render UI on top of a window, after the window's own context.
+///
+/// This allows having several independent contexts in the same window, e.g. a notification layer
+/// that always gets drawn above the main UI. You can create an entity just with this component,
+/// `bevy_egui` will initialize an [`EguiContext`] automatically.
+///
+/// Note that input events are sent to the window context, layers don't receive them unless they are
+/// redirected (see [`HoveredNonWindowEguiContext`] and [`input::EguiBoundTouch`]). A layer can also be restricted
+/// to a part of the window with [`EguiLayerRect`], in which case it receives pointer input when hovered.
+#[cfg(feature = "render")]
+#[derive(Component, Clone, Debug, ExtractComponent)]
+#[require(EguiContext)]
+pub struct EguiLayer {
+    /// A window entity to draw the layer on top of. The window is expected to have an [`EguiContext`].
+    pub window: Entity,
+    /// Layers of the same window are drawn in the ascending order of this value.
+    pub order: i32,
+}
+
+/// Restricts an [`EguiLayer`] context to a rectangle of its window, in logical pixels.
+///
+/// The UI is laid out in the rectangle (i.e. the screen rect of the context equals its size), drawn at its position
+/// and clipped by it. The context receives pointer input while the cursor is over the rectangle (it becomes the
+/// [`HoveredNonWindowEguiContext`]), pointer positions are relative to the rectangle.
+///
+/// This allows embedding Egui into a layout managed by something else, e.g. into a `bevy_ui` node: update the
+/// rectangle every frame with [`EguiLayerRect::from_ui_node`], or enable the `bevy_ui` feature and insert
+/// the `EguiUiNode` component, which does the same.
+#[cfg(feature = "render")]
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, ExtractComponent)]
+pub struct EguiLayerRect(pub bevy_math::Rect);
+
+#[cfg(feature = "render")]
+impl EguiLayerRect {
+    /// Creates a rectangle matching a `bevy_ui` node, given its `ComputedNode::size`, its `GlobalTransform`
+    /// translation (the node center) and `ComputedNode::inverse_scale_factor`.
+    ///
+    /// The size and the center of a node are in physical pixels, the rectangle is converted into logical ones.
+    #[must_use]
+    pub fn from_ui_node(
+        size: bevy_math::Vec2,
+        center: bevy_math::Vec2,
+        inverse_scale_factor: f32,
+    ) -> Self {
+        Self(bevy_math::Rect::from_center_size(
+            center * inverse_scale_factor,
+            size * inverse_scale_factor,
+        ))
+    }
+}
+
+/// Binds an [`EguiLayer`] context to a `bevy_ui` node, so that the UI flows and gets clipped as a part of the node layout.
+///
+/// The [`EguiLayerRect`] of the context is updated from the node's [`ComputedNode`](bevy_ui::ComputedNode) and
+/// [`GlobalTransform`](bevy_transform::components::GlobalTransform) (see [`update_ui_node_layer_rects_system`]).
+/// The node is expected to belong to the [`EguiLayer::window`].
+#[cfg(feature = "bevy_ui")]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+#[require(EguiLayerRect)]
+pub struct EguiUiNode(pub Entity);
+
+/// A resource for storing `bevy_egui` user textures.
+#[derive(Clone, bevy_ecs::system::Resource, ExtractResource)]
+#[cfg(feature = "render")]
+pub struct EguiUserTextures {
+    textures: bevy_utils::HashMap<Handle<Image>, u64>,
+    color_conversions: bevy_utils::HashMap<u64, TextureColorConversion>,
+    free_list: Vec<u64>,
+}
+
+/// A color conversion applied by the Egui shader when sampling a user texture
+/// (see [`EguiUserTextures::add_image_with_color_conversion`]).
+///
+/// Can be used to display video frames without converting them on CPU. The YUV variants expect
+/// [BT.709](https://en.wikipedia.org/wiki/Rec._709) limited range values stored in a non-sRGB texture format
+/// (such as [`TextureFormat::R8Unorm`](bevy_render::render_resource::TextureFormat::R8Unorm) or
+/// [`TextureFormat::Rgba8Unorm`](bevy_render::render_resource::TextureFormat::Rgba8Unorm)).
+#[cfg(feature = "render")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum TextureColorConversion {
+    /// The texture is sampled as RGBA as is, it's expected to have straight (not premultiplied) alpha.
+    #[default]
+    Rgba = 0,
+    /// The red, green and blue channels store Y, U (Cb) and V (Cr) values respectively.
+    Yuv444 = 1,
+    /// A single-channel texture stores the planes of a YUV 4:2:0 frame (I420): a full resolution Y plane followed
+    /// by quarter resolution U and V planes, i.e. the texture is `width` × `height * 3 / 2` for a `width` × `height`
+    /// frame. The texture is sampled without filtering, frame dimensions are expected to be even.
+    Yuv420 = 2,
+    /// The texture has premultiplied alpha (e.g. images rendered by Bevy cameras with premultiplied blending).
+    /// Sampling it as [`TextureColorConversion::Rgba`] would multiply the colors by alpha twice, resulting in
+    /// darkened semi-transparent edges.
+    Premultiplied = 3,
+}
+
+/// User textures visible only to the context they belong to.
+///
+/// Textures registered in the [`EguiUserTextures`] resource are visible to every context. Insert this component
+/// to a context entity to register textures that only this context can use (e.g. to sandbox contexts of
+/// independent tools). The ids are allocated starting from [`EguiContextUserTextures::FIRST_ID`], so they never
+/// clash with the ids of the global textures, which remain available to the context as well.
+#[cfg(feature = "render")]
+#[derive(Component, Clone, ExtractComponent)]
+pub struct EguiContextUserTextures(EguiUserTextures);
+
+#[cfg(feature = "render")]
+impl Default for EguiContextUserTextures {
+    fn default() -> Self {
+        Self(EguiUserTextures::with_first_id(Self::FIRST_ID))
+    }
+}
+
+#[cfg(feature = "render")]
+impl EguiContextUserTextures {
+    /// The first id of [`egui::TextureId::User`] textures allocated per context.
+    pub const FIRST_ID: u64 = 1 << 63;
+
+    /// Can accept either a strong or a weak handle (see [`EguiUserTextures::add_image`]).
+    pub fn add_image(&mut self, image: Handle<Image>) -> egui::TextureId {
+        self.0.add_image(image)
+    }
+
+    /// Can accept either a strong or a weak handle (see [`EguiUserTextures::add_image_with_color_conversion`]).
+    pub fn add_image_with_color_conversion(
+        &mut self,
+        image: Handle<Image>,
+        color_conversion: TextureColorConversion,
+    ) -> egui::TextureId {
+        self.0
+            .add_image_with_color_conversion(image, color_conversion)
+    }
+
+    /// Fallible variant of [`EguiContextUserTextures::add_image`] (see [`EguiUserTextures::try_add_image`]).
+    pub fn try_add_image(&mut self, image: Handle<Image>) -> Option<egui::TextureId> {
+        self.0.try_add_image(image)
+    }
+
+    /// Fallible variant of [`EguiContextUserTextures::add_image_with_color_conversion`]
+    /// (see [`EguiUserTextures::try_add_image_with_color_conversion`]).
+    pub fn try_add_image_with_color_conversion(
+        &mut self,
+        image: Handle<Image>,
+        color_conversion: TextureColorConversion,
+    ) -> Option<egui::TextureId> {
+        self.0
+            .try_add_image_with_color_conversion(image, color_conversion)
+    }
+
+    /// Removes the image handle and an Egui texture id associated with it.
+    pub fn remove_image(&mut self, image: &Handle<Image>) -> Option<egui::TextureId> {
+        self.0.remove_image(image)
+    }
+
+    /// Returns an associated Egui texture id.
+    #[must_use]
+    pub fn image_id(&self, image: &Handle<Image>) -> Option<egui::TextureId> {
+        self.0.image_id(image)
+    }
+}
+
+#[cfg(feature = "render")]
+impl Default for EguiUserTextures {
+    fn default() -> Self {
+        Self::with_first_id(0)
+    }
+}
+
 #[cfg(feature = "render")]
 impl EguiUserTextures {
+    /// Creates an empty texture registry allocating [`egui::TextureId::User`] ids starting from `first_id`.
+    ///
+    /// Useful for partitioning the `User` id space with other libraries that allocate their own ids.
+    /// Note that ids starting from [`EguiContextUserTextures::FIRST_ID`] are reserved for per-context textures.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_egui::EguiUserTextures;
+    /// App::new().insert_resource(EguiUserTextures::with_first_id(1_000_000));
+    /// ```
+    pub fn with_first_id(first_id: u64) -> Self {
+        Self {
+            textures: bevy_utils::HashMap::new(),
+            color_conversions: bevy_utils::HashMap::new(),
+            free_list: vec![first_id],
+        }
+    }
+
+    /// Sets the id that newly allocated [`egui::TextureId::User`] ids start from (see [`EguiUserTextures::with_first_id`]).
+    ///
+    /// Meant to be called at startup, before any image is added: changing the base mid-run is unsupported,
+    /// as ids freed later are still reused and may end up outside of the new range.
+    pub fn set_id_base(&mut self, base: u64) {
+        if !self.textures.is_empty() {
+            log::warn!(
+                "Changing the id base of EguiUserTextures with images already added is unsupported"
+            );
+        }
+        self.free_list = vec![base];
+    }
+
     /// Can accept either a strong or a weak handle.
     ///
     /// You may want to pass a weak handle if you control removing texture assets in your
@@ -660,19 +1801,63 @@ impl EguiUserTextures {
     ///
     /// You'll want to pass a strong handle if a texture is used only in Egui and there are no
     /// handle copies stored anywhere else.
+    ///
+    /// The image is expected to have straight (not premultiplied) alpha, as images loaded from PNG files do,
+    /// its colors get premultiplied by the Egui shader. Use [`TextureColorConversion::Premultiplied`]
+    /// for images with premultiplied alpha.
     pub fn add_image(&mut self, image: Handle<Image>) -> egui::TextureId {
-        let id = *self.textures.entry(image.clone()).or_insert_with(|| {
-            let id = self
-                .free_list
-                .pop()
-                .expect("free list must contain at least 1 element");
-            log::debug!("Add a new image (id: {}, handle: {:?})", id, image);
-            if self.free_list.is_empty() {
-                self.free_list.push(id.checked_add(1).expect("out of ids"));
-            }
-            id
-        });
-        egui::TextureId::User(id)
+        self.try_add_image(image).expect("out of ids")
+    }
+
+    /// Fallible variant of [`EguiUserTextures::add_image`], returns [`None`] if there are no ids left.
+    pub fn try_add_image(&mut self, image: Handle<Image>) -> Option<egui::TextureId> {
+        if let Some(&id) = self.textures.get(&image) {
+            return Some(egui::TextureId::User(id));
+        }
+        let id = self.free_list.pop()?;
+        log::debug!("Add a new image (id: {}, handle: {:?})", id, image);
+        if self.free_list.is_empty() {
+            // The free list stays empty once the last id is taken.
+            self.free_list.extend(id.checked_add(1));
+        }
+        self.textures.insert(image, id);
+        Some(egui::TextureId::User(id))
+    }
+
+    /// Same as [`EguiUserTextures::add_image`], but also sets a color conversion applied when the texture is sampled.
+    ///
+    /// If the image has already been added, only its color conversion gets updated.
+    pub fn add_image_with_color_conversion(
+        &mut self,
+        image: Handle<Image>,
+        color_conversion: TextureColorConversion,
+    ) -> egui::TextureId {
+        self.try_add_image_with_color_conversion(image, color_conversion)
+            .expect("out of ids")
+    }
+
+    /// Fallible variant of [`EguiUserTextures::add_image_with_color_conversion`], returns [`None`] if there are no ids left.
+    pub fn try_add_image_with_color_conversion(
+        &mut self,
+        image: Handle<Image>,
+        color_conversion: TextureColorConversion,
+    ) -> Option<egui::TextureId> {
+        let texture_id = self.try_add_image(image)?;
+        let egui::TextureId::User(id) = texture_id else {
+            unreachable!();
+        };
+        if color_conversion == TextureColorConversion::default() {
+            self.color_conversions.remove(&id);
+        } else {
+            self.color_conversions.insert(id, color_conversion);
+        }
+        Some(texture_id)
+    }
+
+    /// Returns the color conversion of a texture (see [`EguiUserTextures::add_image_with_color_conversion`]).
+    #[must_use]
+    pub fn color_conversion(&self, id: u64) -> TextureColorConversion {
+        self.color_conversions.get(&id).copied().unwrap_or_default()
     }
 
     /// Removes the image handle and an Egui texture id associated with it.
@@ -680,6 +1865,7 @@ impl EguiUserTextures {
         let id = self.textures.remove(image);
         log::debug!("Remove image (id: {:?}, handle: {:?})", id, image);
         if let Some(id) = id {
+            self.color_conversions.remove(&id);
             self.free_list.push(id);
         }
         id.map(egui::TextureId::User)
@@ -707,7 +1893,8 @@ pub struct RenderTargetSize {
 }
 
 impl RenderTargetSize {
-    fn new(physical_width: f32, physical_height: f32, scale_factor: f32) -> Self {
+    /// Creates a size from physical dimensions and a scale factor.
+    pub fn new(physical_width: f32, physical_height: f32, scale_factor: f32) -> Self {
         Self {
             physical_width,
             physical_height,
@@ -715,16 +1902,30 @@ impl RenderTargetSize {
         }
     }
 
-    /// Returns the width of the render target.
+    /// Returns the width of the render target (zero if the size isn't known yet).
     #[inline]
     pub fn width(&self) -> f32 {
-        self.physical_width / self.scale_factor
+        if self.scale_factor > 0.0 {
+            self.physical_width / self.scale_factor
+        } else {
+            0.0
+        }
     }
 
-    /// Returns the height of the render target.
+    /// Returns the height of the render target (zero if the size isn't known yet).
     #[inline]
     pub fn height(&self) -> f32 {
-        self.physical_height / self.scale_factor
+        if self.scale_factor > 0.0 {
+            self.physical_height / self.scale_factor
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns `true` if the render target has a non-zero area (e.g. it's not a minimized window).
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.physical_width > 0.0 && self.physical_height > 0.0 && self.scale_factor > 0.0
     }
 }
 
@@ -746,17 +1947,57 @@ pub enum EguiStartupSet {
 pub enum EguiPreUpdateSet {
     /// Initializes Egui contexts for newly created render targets.
     InitContexts,
-    /// Reads Egui inputs (keyboard, mouse, etc) and writes them into the [`EguiInput`] resource.
-    ///
-    /// To modify the input, you can hook your system like this:
+    /// Reads Egui inputs (keyboard, mouse, etc) and writes them into the [`EguiInput`] component.
     ///
-    /// `system.after(EguiPreUpdateSet::ProcessInput).before(EguiSet::BeginPass)`.
+    /// To modify the input, add your system to [`EguiPreUpdateSet::ModifyInput`].
     ProcessInput,
+    /// The supported injection point for modifying [`EguiInput`] before it's consumed by [`begin_pass_system`].
+    ///
+    /// Systems in this set can edit the [`EguiInput`] component (e.g. push synthesized events to
+    /// [`egui::RawInput::events`] or remove unwanted ones):
+    ///
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_egui::{egui, EguiInput, EguiPreUpdateSet};
+    ///
+    /// fn focus_next_widget_system(mut inputs: Query<&mut EguiInput>) {
+    ///     for mut input in inputs.iter_mut() {
+    ///         input.events.push(egui::Event::Key {
+    ///             key: egui::Key::Tab,
+    ///             physical_key: None,
+    ///             pressed: true,
+    ///             repeat: false,
+    ///             modifiers: egui::Modifiers::NONE,
+    ///         });
+    ///     }
+    /// }
+    ///
+    /// App::new().add_systems(
+    ///     PreUpdate,
+    ///     focus_next_widget_system.in_set(EguiPreUpdateSet::ModifyInput),
+    /// );
+    /// ```
+    ///
+    /// Note that [`EguiInputEvent`] events (and [`EguiContexts::send_event`]) sent from this set
+    /// will be processed only on the next frame, as [`EguiPreUpdateSet::ProcessInput`] has already run.
+    ModifyInput,
     /// Begins the `egui` pass.
     BeginPass,
 }
 
 /// Subsets of the [`EguiPreUpdateSet::ProcessInput`] set.
+///
+/// The input systems don't send [`EguiInputEvent`] events directly, they push them to [`EguiInputTimeline`], which
+/// sends them at the beginning of [`EguiInputSet::WriteEguiEvents`] in the order the Bevy events they originate from
+/// were received in (as reported by [`bevy_window::WindowEvent`]), regardless of the event types. For example, pressing
+/// a mouse button and moving the cursor within the same frame results in a click at the position the button was
+/// pressed at. Thus the events that end up in [`EguiInput`] are deterministic: given the same Bevy input events
+/// (including [`bevy_window::WindowEvent`]), Egui will always receive the same event sequence.
+///
+/// Input events that apps send directly (i.e. without a matching [`bevy_window::WindowEvent`]) are ordered after
+/// the window events, keeping the order of their types: window pointer moves, pointer buttons, window touches,
+/// non-window touches, mouse wheel, keyboard and IME events. [`EguiInputEvent`] events sent directly
+/// (e.g. with [`EguiContexts::send_event`]) get to Egui before the ones of the timeline.
 #[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
 pub enum EguiInputSet {
     /// Reads key modifiers state and pointer positions.
@@ -771,6 +2012,14 @@ pub enum EguiInputSet {
     WriteEguiEvents,
 }
 
+/// A system set for running Egui passes manually (see [`EguiContextSettings::run_manually`]).
+///
+/// The set is configured to run after input is processed ([`EguiPreUpdateSet::BeginPass`]) when added to [`PreUpdate`],
+/// and before Egui output is processed ([`EguiPostUpdateSet::ProcessOutput`]) when added to [`PostUpdate`].
+/// Systems in [`Update`] don't need any additional ordering, but can still be added to this set for consistency.
+#[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
+pub struct EguiManualPass;
+
 /// System sets that run during the [`PostUpdate`] schedule.
 #[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
 pub enum EguiPostUpdateSet {
@@ -782,6 +2031,22 @@ pub enum EguiPostUpdateSet {
     PostProcessOutput,
 }
 
+/// System sets that run during the [`Render`] schedule of the render app.
+#[cfg(feature = "render")]
+#[derive(SystemSet, Clone, Hash, Debug, Eq, PartialEq)]
+pub enum EguiRenderSet {
+    /// Prepares Egui transforms and render target data (belongs to [`RenderSet::Prepare`]).
+    Prepare,
+    /// Queues Egui bind groups and pipelines (belongs to [`RenderSet::Queue`]).
+    Queue,
+    /// Runs after the render graph (including Egui nodes) has been executed and command buffers have been submitted,
+    /// but before [`RenderSet::Cleanup`].
+    ///
+    /// Note that submitted GPU work isn't guaranteed to be finished at this point, reading textures back
+    /// still requires mapping buffers asynchronously.
+    PostPass,
+}
+
 impl Plugin for EguiPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<EguiGlobalSettings>();
@@ -789,11 +2054,17 @@ impl Plugin for EguiPlugin {
         app.init_resource::<EguiGlobalSettings>();
         app.init_resource::<ModifierKeysState>();
         app.add_event::<EguiInputEvent>();
+        app.init_resource::<EguiInputTimeline>();
+        app.add_event::<EguiRepaintRequested>();
+        app.add_observer(init_new_contexts_observer);
 
         #[cfg(feature = "render")]
         {
             app.init_resource::<EguiManagedTextures>();
+            app.init_resource::<EguiTextureMemoryStats>();
             app.init_resource::<EguiUserTextures>();
+            app.init_asset::<EguiFont>();
+            app.init_asset_loader::<EguiFontLoader>();
             app.add_plugins(ExtractResourcePlugin::<EguiUserTextures>::default());
             app.add_plugins(ExtractResourcePlugin::<ExtractedEguiManagedTextures>::default());
             app.add_plugins(ExtractComponentPlugin::<EguiContext>::default());
@@ -801,19 +2072,29 @@ impl Plugin for EguiPlugin {
             app.add_plugins(ExtractComponentPlugin::<RenderTargetSize>::default());
             app.add_plugins(ExtractComponentPlugin::<EguiRenderOutput>::default());
             app.add_plugins(ExtractComponentPlugin::<EguiRenderToImage>::default());
+            app.add_plugins(ExtractComponentPlugin::<EguiRenderToTextureView>::default());
+            app.add_plugins(ExtractComponentPlugin::<EguiLayer>::default());
+            app.add_plugins(ExtractComponentPlugin::<EguiLayerRect>::default());
+            app.add_plugins(ExtractComponentPlugin::<EguiPassThrottle>::default());
+            app.add_plugins(ExtractComponentPlugin::<EguiLayerBlendStates>::default());
+            app.add_plugins(ExtractComponentPlugin::<EguiContextUserTextures>::default());
         }
 
         #[cfg(target_arch = "wasm32")]
         app.init_non_send_resource::<SubscribedEvents>();
 
-        #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
-        app.init_resource::<EguiClipboard>();
+        #[cfg(feature = "manage_clipboard")]
+        {
+            app.init_resource::<EguiClipboard>();
+            app.add_event::<EguiImagePasteEvent>();
+        }
 
         app.configure_sets(
             PreUpdate,
             (
                 EguiPreUpdateSet::InitContexts,
                 EguiPreUpdateSet::ProcessInput.after(InputSystem),
+                EguiPreUpdateSet::ModifyInput,
                 EguiPreUpdateSet::BeginPass,
             )
                 .chain(),
@@ -837,6 +2118,13 @@ impl Plugin for EguiPlugin {
             )
                 .chain(),
         );
+        app.configure_sets(PreUpdate, EguiManualPass.after(EguiPreUpdateSet::BeginPass));
+        app.configure_sets(
+            PostUpdate,
+            EguiManualPass
+                .after(EguiPostUpdateSet::EndPass)
+                .before(EguiPostUpdateSet::ProcessOutput),
+        );
 
         // Startup systems.
         #[cfg(all(feature = "manage_clipboard", target_arch = "wasm32"))]
@@ -849,6 +2137,9 @@ impl Plugin for EguiPlugin {
                 setup_new_windows_system,
                 apply_deferred,
                 update_ui_size_and_scale_system,
+                update_contexts_options_system,
+                update_contexts_input_options_system,
+                update_contexts_style_system,
             )
                 .chain()
                 .in_set(EguiStartupSet::InitContexts),
@@ -861,6 +2152,9 @@ impl Plugin for EguiPlugin {
                 setup_new_windows_system,
                 apply_deferred,
                 update_ui_size_and_scale_system,
+                update_contexts_options_system,
+                update_contexts_input_options_system,
+                update_contexts_style_system,
             )
                 .chain()
                 .in_set(EguiPreUpdateSet::InitContexts),
@@ -869,13 +2163,18 @@ impl Plugin for EguiPlugin {
             PreUpdate,
             (
                 (
+                    read_window_events_system,
                     write_modifiers_keys_state_system.run_if(input_system_is_enabled(|s| {
                         s.run_write_modifiers_keys_state_system
                     })),
                     write_window_pointer_moved_events_system.run_if(input_system_is_enabled(|s| {
                         s.run_write_window_pointer_moved_events_system
                     })),
+                    write_window_pointer_motion_events_system.run_if(input_system_is_enabled(
+                        |s| s.run_write_window_pointer_motion_events_system,
+                    )),
                 )
+                    .chain()
                     .in_set(EguiInputSet::InitReading),
                 (
                     write_pointer_button_events_system.run_if(input_system_is_enabled(|s| {
@@ -885,6 +2184,7 @@ impl Plugin for EguiPlugin {
                         s.run_write_window_touch_events_system
                     })),
                 )
+                    .chain()
                     .in_set(EguiInputSet::FocusContext),
                 (
                     write_non_window_pointer_moved_events_system.run_if(input_system_is_enabled(
@@ -901,13 +2201,73 @@ impl Plugin for EguiPlugin {
                     })),
                     write_ime_events_system
                         .run_if(input_system_is_enabled(|s| s.run_write_ime_events_system)),
+                    write_window_focus_events_system.run_if(input_system_is_enabled(|s| {
+                        s.run_write_window_focus_events_system
+                    })),
+                    release_touch_pointer_system.run_if(input_system_is_enabled(|s| {
+                        s.run_write_window_touch_events_system
+                    })),
                 )
+                    .chain()
                     .in_set(EguiInputSet::ReadBevyEvents),
-                write_egui_input_system.in_set(EguiInputSet::WriteEguiEvents),
+                (flush_egui_input_timeline_system, write_egui_input_system)
+                    .chain()
+                    .in_set(EguiInputSet::WriteEguiEvents),
             )
                 .chain()
                 .in_set(EguiPreUpdateSet::ProcessInput),
         );
+        #[cfg(feature = "render")]
+        app.add_systems(
+            PreUpdate,
+            update_layer_rect_hovered_context_system
+                .in_set(EguiPreUpdateSet::ProcessInput)
+                .in_set(EguiInputSet::InitReading),
+        );
+        #[cfg(feature = "accesskit")]
+        app.add_systems(
+            PreUpdate,
+            write_accessibility_focus_events_system
+                .run_if(input_system_is_enabled(|s| {
+                    s.run_write_accessibility_focus_events_system
+                }))
+                .after(write_window_focus_events_system)
+                .in_set(EguiPreUpdateSet::ProcessInput)
+                .in_set(EguiInputSet::ReadBevyEvents),
+        );
+        #[cfg(feature = "accesskit")]
+        app.add_systems(
+            PreUpdate,
+            // The events are registered by `bevy_winit`, they don't exist in headless apps.
+            write_accessibility_action_requests_system
+                .run_if(resource_exists::<Events<bevy_a11y::ActionRequest>>)
+                .run_if(input_system_is_enabled(|s| {
+                    s.run_write_accessibility_action_requests_system
+                }))
+                .in_set(EguiPreUpdateSet::ProcessInput)
+                .in_set(EguiInputSet::ReadBevyEvents),
+        );
+        #[cfg(feature = "render")]
+        app.add_systems(
+            PreUpdate,
+            update_contexts_fonts_system
+                .after(update_contexts_style_system)
+                .in_set(EguiPreUpdateSet::InitContexts),
+        );
+        #[cfg(feature = "bevy_ui")]
+        app.add_systems(
+            PreUpdate,
+            update_ui_node_layer_rects_system
+                .before(update_ui_size_and_scale_system)
+                .in_set(EguiPreUpdateSet::InitContexts),
+        );
+        #[cfg(feature = "render")]
+        app.add_systems(
+            PreUpdate,
+            update_pass_throttle_system
+                .after(update_ui_size_and_scale_system)
+                .in_set(EguiPreUpdateSet::InitContexts),
+        );
         app.add_systems(
             PreUpdate,
             begin_pass_system.in_set(EguiPreUpdateSet::BeginPass),
@@ -951,6 +2311,7 @@ impl Plugin for EguiPlugin {
                         .run_if(input_system_is_enabled(|s| {
                             s.run_write_text_agent_channel_events_system
                         }))
+                        .after(write_ime_events_system)
                         .in_set(EguiPreUpdateSet::ProcessInput)
                         .in_set(EguiInputSet::ReadBevyEvents),
                 );
@@ -971,6 +2332,7 @@ impl Plugin for EguiPlugin {
                     .run_if(input_system_is_enabled(|s| {
                         s.run_write_web_clipboard_events_system
                     }))
+                    .after(write_ime_events_system)
                     .in_set(EguiPreUpdateSet::ProcessInput)
                     .in_set(EguiInputSet::ReadBevyEvents),
             );
@@ -985,9 +2347,34 @@ impl Plugin for EguiPlugin {
             PostUpdate,
             process_output_system.in_set(EguiPostUpdateSet::ProcessOutput),
         );
+        #[cfg(feature = "virtual_keyboard")]
+        app.add_systems(
+            PostUpdate,
+            virtual_keyboard::update_virtual_keyboard_system
+                .in_set(EguiPostUpdateSet::PostProcessOutput),
+        );
+        // Picking systems are skipped if `bevy_picking` isn't set up (e.g. in headless apps).
         #[cfg(feature = "picking")]
-        app.add_systems(PostUpdate, capture_pointer_input_system);
+        app.add_systems(
+            PostUpdate,
+            capture_pointer_input_system
+                .run_if(resource_exists::<Events<bevy_picking::backend::PointerHits>>),
+        );
+        #[cfg(all(feature = "picking", feature = "render"))]
+        app.add_systems(
+            PostUpdate,
+            capture_non_window_pointer_input_system.run_if(
+                resource_exists::<bevy_picking::backend::ray::RayMap>
+                    .and(resource_exists::<Events<bevy_picking::backend::PointerHits>>),
+            ),
+        );
 
+        #[cfg(feature = "render")]
+        app.add_systems(
+            PostUpdate,
+            update_render_to_image_readback_system.in_set(EguiPostUpdateSet::PostProcessOutput),
+        )
+        .add_observer(update_render_to_image_frame_observer);
         #[cfg(feature = "render")]
         app.add_systems(
             PostUpdate,
@@ -1024,6 +2411,10 @@ impl Plugin for EguiPlugin {
                 .init_resource::<SpecializedRenderPipelines<EguiPipeline>>()
                 .init_resource::<EguiTransforms>()
                 .init_resource::<EguiRenderData>()
+                .init_resource::<egui_node::EguiRenderToImageBatch>()
+                .init_resource::<render_systems::EguiTextureBindGroups>()
+                .init_resource::<render_systems::EguiPipelines>()
+                .init_resource::<render_systems::EguiBlendStatePipelines>()
                 .add_systems(
                     // Seems to be just the set to add/remove nodes, as it'll run before
                     // `RenderSet::ExtractCommands` where render nodes get updated.
@@ -1032,23 +2423,41 @@ impl Plugin for EguiPlugin {
                         render_systems::setup_new_egui_nodes_system,
                         render_systems::teardown_window_nodes_system,
                         render_systems::teardown_render_to_image_nodes_system,
+                        render_systems::teardown_render_to_texture_view_nodes_system,
+                        render_systems::teardown_layer_nodes_system,
+                    ),
+                )
+                .configure_sets(
+                    Render,
+                    (
+                        EguiRenderSet::Prepare.in_set(RenderSet::Prepare),
+                        EguiRenderSet::Queue.in_set(RenderSet::Queue),
+                        EguiRenderSet::PostPass
+                            .after(RenderSet::Render)
+                            .before(RenderSet::Cleanup),
                     ),
                 )
                 .add_systems(
                     Render,
-                    render_systems::prepare_egui_transforms_system.in_set(RenderSet::Prepare),
+                    render_systems::prepare_egui_transforms_system.in_set(EguiRenderSet::Prepare),
+                )
+                .add_systems(
+                    Render,
+                    render_systems::prepare_egui_render_target_data.in_set(EguiRenderSet::Prepare),
                 )
                 .add_systems(
                     Render,
-                    render_systems::prepare_egui_render_target_data.in_set(RenderSet::Prepare),
+                    render_systems::queue_bind_groups_system.in_set(EguiRenderSet::Queue),
                 )
                 .add_systems(
                     Render,
-                    render_systems::queue_bind_groups_system.in_set(RenderSet::Queue),
+                    render_systems::report_missing_textures_system
+                        .after(render_systems::queue_bind_groups_system)
+                        .in_set(EguiRenderSet::Queue),
                 )
                 .add_systems(
                     Render,
-                    render_systems::queue_pipelines_system.in_set(RenderSet::Queue),
+                    render_systems::queue_pipelines_system.in_set(EguiRenderSet::Queue),
                 );
         }
     }
@@ -1065,6 +2474,56 @@ fn input_system_is_enabled(
 #[derive(bevy_ecs::system::Resource, Deref, DerefMut, Default)]
 pub struct EguiManagedTextures(pub bevy_utils::HashMap<(Entity, u64), EguiManagedTexture>);
 
+#[cfg(feature = "render")]
+impl EguiManagedTextures {
+    /// Forces all the managed textures to be re-uploaded to the GPU.
+    ///
+    /// Image assets that still exist get marked as modified, so that the render world prepares them again,
+    /// missing assets get re-created from [`EguiManagedTexture::color_image`].
+    ///
+    /// Call this after the GPU resources have been lost, for example, after your app re-creates the render device
+    /// or a web canvas (which may happen on some Android devices or in browsers). Otherwise, the UI may be rendered
+    /// with missing font atlases until Egui sends a delta for them.
+    pub fn invalidate_all(&mut self, image_assets: &mut Assets<Image>) {
+        for managed_texture in self.0.values_mut() {
+            // Getting a mutable reference is enough for the asset to be marked as modified.
+            if image_assets.get_mut(&managed_texture.handle).is_none() {
+                managed_texture.handle = image_assets.add(egui_node::color_image_as_bevy_image(
+                    &managed_texture.color_image,
+                    managed_texture.sampler.clone(),
+                ));
+            }
+        }
+    }
+}
+
+/// Estimated GPU memory used by textures allocated by Egui (font atlases, etc).
+///
+/// The estimate is `width * height * 4` bytes per texture (textures are RGBA8 and have no mip chains).
+/// The resource gets updated by [`update_egui_textures_system`] and [`free_egui_textures_system`].
+#[cfg(feature = "render")]
+#[derive(bevy_ecs::system::Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EguiTextureMemoryStats {
+    /// Total size of all managed textures, in bytes.
+    pub total_bytes: usize,
+    /// Size of managed textures of each context, in bytes.
+    pub context_bytes: bevy_utils::HashMap<Entity, usize>,
+}
+
+#[cfg(feature = "render")]
+impl EguiTextureMemoryStats {
+    fn from_managed_textures(egui_managed_textures: &EguiManagedTextures) -> Self {
+        let mut stats = Self::default();
+        for (&(entity, _), managed_texture) in egui_managed_textures.iter() {
+            let [width, height] = managed_texture.color_image.size;
+            let bytes = width * height * 4;
+            stats.total_bytes += bytes;
+            *stats.context_bytes.entry(entity).or_default() += bytes;
+        }
+        stats
+    }
+}
+
 /// Represents a texture allocated and painted by Egui.
 #[cfg(feature = "render")]
 pub struct EguiManagedTexture {
@@ -1072,6 +2531,8 @@ pub struct EguiManagedTexture {
     pub handle: Handle<Image>,
     /// Stored in full so we can do partial updates (which bevy doesn't support).
     pub color_image: egui::ColorImage,
+    /// Sampler of the last texture update.
+    pub sampler: ImageSampler,
 }
 
 /// Adds bevy_egui components to newly created windows.
@@ -1085,7 +2546,21 @@ pub fn setup_new_windows_system(
     }
 }
 
-#[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
+/// Calls [`EguiContextInit`] (if the resource exists) for newly created contexts.
+pub fn init_new_contexts_observer(
+    trigger: Trigger<OnAdd, EguiContext>,
+    mut contexts: Query<&mut EguiContext>,
+    context_init: Option<Res<EguiContextInit>>,
+) {
+    let Some(context_init) = context_init else {
+        return;
+    };
+    if let Ok(mut context) = contexts.get_mut(trigger.entity()) {
+        (context_init.0)(context.get_mut());
+    }
+}
+
+#[cfg(feature = "manage_clipboard")]
 impl EguiClipboard {
     /// Places the text onto the clipboard.
     pub fn set_text(&mut self, contents: &str) {
@@ -1110,13 +2585,32 @@ impl EguiClipboard {
         self.set_image_impl(image);
     }
 
+    /// Gets clipboard image content. Returns [`None`] if the clipboard doesn't contain an image,
+    /// or if the platform doesn't support reading images (web, Android).
+    #[must_use]
+    pub fn get_image(&mut self) -> Option<egui::ColorImage> {
+        self.get_image_impl()
+    }
+
+    /// Gets the text of the primary selection (pasted with the middle mouse button on Linux).
+    /// Returns [`None`] if the selection is empty or on platforms that don't have a primary selection.
+    #[must_use]
+    pub fn get_primary_selection(&mut self) -> Option<String> {
+        self.get_primary_selection_impl()
+    }
+
+    /// Places the text onto the primary selection, does nothing on platforms that don't have one.
+    pub fn set_primary_selection(&mut self, contents: &str) {
+        self.set_primary_selection_impl(contents);
+    }
+
     /// Receives a clipboard event sent by the `copy`/`cut`/`paste` listeners.
     #[cfg(target_arch = "wasm32")]
     pub fn try_receive_clipboard_event(&self) -> Option<web_clipboard::WebClipboardEvent> {
         self.clipboard.try_receive_clipboard_event()
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
     fn set_text_impl(&mut self, contents: &str) {
         if let Some(mut clipboard) = self.get() {
             if let Err(err) = clipboard.set_text(contents.to_owned()) {
@@ -1125,12 +2619,12 @@ impl EguiClipboard {
         }
     }
 
-    #[cfg(target_arch = "wasm32")]
+    #[cfg(any(target_arch = "wasm32", target_os = "android"))]
     fn set_text_impl(&mut self, contents: &str) {
         self.clipboard.set_text(contents);
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
     fn get_text_impl(&mut self) -> Option<String> {
         if let Some(mut clipboard) = self.get() {
             match clipboard.get_text() {
@@ -1143,13 +2637,13 @@ impl EguiClipboard {
         None
     }
 
-    #[cfg(target_arch = "wasm32")]
+    #[cfg(any(target_arch = "wasm32", target_os = "android"))]
     #[allow(clippy::unnecessary_wraps)]
     fn get_text_impl(&mut self) -> Option<String> {
         self.clipboard.get_text()
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
     fn set_image_impl(&mut self, image: &egui::ColorImage) {
         if let Some(mut clipboard) = self.get() {
             if let Err(err) = clipboard.set_image(arboard::ImageData {
@@ -1162,36 +2656,154 @@ impl EguiClipboard {
         }
     }
 
-    #[cfg(target_arch = "wasm32")]
+    #[cfg(any(target_arch = "wasm32", target_os = "android"))]
     fn set_image_impl(&mut self, image: &egui::ColorImage) {
         self.clipboard.set_image(image);
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    fn get(&self) -> Option<RefMut<Clipboard>> {
-        self.clipboard
-            .get_or(|| {
-                Clipboard::new()
-                    .map(RefCell::new)
-                    .map_err(|err| {
-                        log::error!("Failed to initialize clipboard: {:?}", err);
-                    })
-                    .ok()
-            })
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    fn get_image_impl(&mut self) -> Option<egui::ColorImage> {
+        let mut clipboard = self.get()?;
+        match clipboard.get_image() {
+            Ok(image) => Some(egui::ColorImage::from_rgba_unmultiplied(
+                [image.width, image.height],
+                &image.bytes,
+            )),
+            Err(arboard::Error::ContentNotAvailable) => None,
+            Err(err) => {
+                log::error!("Failed to get clipboard contents: {:?}", err);
+                None
+            }
+        }
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+    fn get_image_impl(&mut self) -> Option<egui::ColorImage> {
+        None
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    ))]
+    fn get_primary_selection_impl(&mut self) -> Option<String> {
+        use arboard::GetExtLinux;
+
+        let mut clipboard = self.get()?;
+        match clipboard
+            .get()
+            .clipboard(arboard::LinuxClipboardKind::Primary)
+            .text()
+        {
+            Ok(contents) => Some(contents),
+            Err(arboard::Error::ContentNotAvailable) => None,
+            Err(err) => {
+                log::error!("Failed to get primary selection contents: {:?}", err);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    )))]
+    fn get_primary_selection_impl(&mut self) -> Option<String> {
+        None
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    ))]
+    fn set_primary_selection_impl(&mut self, contents: &str) {
+        use arboard::SetExtLinux;
+
+        if let Some(mut clipboard) = self.get() {
+            if let Err(err) = clipboard
+                .set()
+                .clipboard(arboard::LinuxClipboardKind::Primary)
+                .text(contents.to_owned())
+            {
+                log::error!("Failed to set primary selection contents: {:?}", err);
+            }
+        }
+    }
+
+    #[cfg(not(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    )))]
+    fn set_primary_selection_impl(&mut self, _contents: &str) {}
+
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    fn get(&self) -> Option<RefMut<Clipboard>> {
+        self.clipboard
+            .get_or(|| {
+                Clipboard::new()
+                    .map(RefCell::new)
+                    .map_err(|err| {
+                        log::error!("Failed to initialize clipboard: {:?}", err);
+                    })
+                    .ok()
+            })
             .as_ref()
             .map(|cell| cell.borrow_mut())
     }
 }
 
+/// Is sent when an image gets pasted into a context (with the `Ctrl+V`/`Cmd+V` shortcut) and the clipboard
+/// has no text, or the clipboard has an image and [`EguiContextSettings::prefer_image_paste`] is enabled.
+///
+/// Egui widgets don't accept images, so handling such events is up to the app.
+#[cfg(feature = "manage_clipboard")]
+#[derive(Event, Clone)]
+pub struct EguiImagePasteEvent {
+    /// Context the image was pasted into.
+    pub context: Entity,
+    /// Pasted image.
+    pub image: egui::ColorImage,
+}
+
 /// The ordering value used for [`bevy_picking`].
 #[cfg(feature = "picking")]
 pub const PICKING_ORDER: f32 = 1_000_000.0;
 
+/// Makes a context click-through for [`bevy_picking`]: the pointer is never captured by the context,
+/// regardless of [`EguiContextSettings::capture_pointer_input`].
+///
+/// Unlike the settings field, the marker can be added and removed frequently (e.g. for an overlay that's only
+/// interactive at times) without triggering change detection and extraction of [`EguiContextSettings`].
+/// The marker only affects picking, Egui still receives pointer input.
+#[cfg(feature = "picking")]
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct EguiClickThrough;
+
+/// Decides whether Egui captures a pointer, i.e. whether [`bevy_picking`] should treat the pointer as blocked by the context.
+///
+/// This is the predicate used by [`capture_pointer_input_system`] and [`capture_non_window_pointer_input_system`],
+/// custom picking integrations can call it to stay consistent with them. `pointer_over_area` is usually
+/// [`egui::Context::is_pointer_over_area`]. Note that the systems skip contexts marked with [`EguiClickThrough`]
+/// before calling the predicate.
+#[cfg(feature = "picking")]
+pub fn egui_wants_pointer(
+    ctx: &egui::Context,
+    settings: &EguiContextSettings,
+    pointer_over_area: bool,
+) -> bool {
+    settings.capture_pointer_input
+        && (ctx.wants_pointer_input()
+            || settings.capture_pointer_input_over_area && pointer_over_area)
+}
+
 /// Captures pointers on egui windows for [`bevy_picking`].
 #[cfg(feature = "picking")]
 pub fn capture_pointer_input_system(
     pointers: Query<(&PointerId, &PointerLocation)>,
-    mut egui_context: Query<(Entity, &mut EguiContext, &EguiContextSettings), With<Window>>,
+    mut egui_context: Query<
+        (Entity, &mut EguiContext, &EguiContextSettings),
+        (With<Window>, Without<EguiClickThrough>),
+    >,
     mut output: EventWriter<PointerHits>,
 ) {
     use helpers::QueryHelper;
@@ -1202,7 +2814,8 @@ pub fn capture_pointer_input_system(
     {
         if let NormalizedRenderTarget::Window(id) = location.target {
             if let Some((entity, mut ctx, settings)) = egui_context.get_some_mut(id.entity()) {
-                if settings.capture_pointer_input && ctx.get_mut().wants_pointer_input() {
+                let ctx = ctx.get_mut();
+                if egui_wants_pointer(ctx, settings, ctx.is_pointer_over_area()) {
                     let entry = (entity, HitData::new(entity, 0.0, None, None));
                     output.send(PointerHits::new(
                         *pointer,
@@ -1215,60 +2828,194 @@ pub fn capture_pointer_input_system(
     }
 }
 
+/// Captures pointers on non-window (e.g. world-space) Egui contexts for [`bevy_picking`].
+///
+/// Unlike [`capture_pointer_input_system`], which reports window contexts at a fixed depth on top of everything,
+/// this system reports hits at their actual depth, so that overlapping panels in 3D space are sorted correctly.
+/// A context is treated as a flat panel: the pointer rays of [`RayMap`](bevy_picking::backend::ray::RayMap) get
+/// intersected with the plane of its [`Aabb`](bevy_render::primitives::Aabb) (computed by Bevy for meshes) along the
+/// thinnest axis, transformed by its [`GlobalTransform`](bevy_transform::components::GlobalTransform).
+/// This matches an [`EguiRenderToImage`] context that has a quad mesh (e.g. [`Plane3d`](bevy_math::primitives::Plane3d))
+/// on the same entity, contexts without these components aren't captured.
+#[cfg(all(feature = "picking", feature = "render"))]
+pub fn capture_non_window_pointer_input_system(
+    ray_map: Res<bevy_picking::backend::ray::RayMap>,
+    mut egui_context: Query<
+        (
+            Entity,
+            &mut EguiContext,
+            &EguiContextSettings,
+            &bevy_transform::components::GlobalTransform,
+            &bevy_render::primitives::Aabb,
+        ),
+        (With<EguiRenderToImage>, Without<EguiClickThrough>),
+    >,
+    cameras: Query<&Camera>,
+    mut output: EventWriter<PointerHits>,
+) {
+    for (ray_id, ray) in ray_map.iter() {
+        let Ok(camera) = cameras.get(ray_id.camera) else {
+            continue;
+        };
+        if !camera.is_active {
+            continue;
+        }
+
+        let mut picks = Vec::new();
+        for (entity, mut ctx, settings, transform, aabb) in egui_context.iter_mut() {
+            let ctx = ctx.get_mut();
+            if !egui_wants_pointer(ctx, settings, ctx.is_pointer_over_area()) {
+                continue;
+            }
+            if let Some((depth, position, normal)) = ray_panel_intersection(ray, transform, aabb) {
+                picks.push((
+                    entity,
+                    HitData::new(ray_id.camera, depth, Some(position), Some(normal)),
+                ));
+            }
+        }
+        if !picks.is_empty() {
+            output.send(PointerHits::new(ray_id.pointer, picks, camera.order as f32));
+        }
+    }
+}
+
+/// Intersects a ray with the plane of an [`Aabb`](bevy_render::primitives::Aabb) along its thinnest axis,
+/// returns the distance from the ray origin, the world position and the normal (facing the ray) of the hit.
+#[cfg(all(feature = "picking", feature = "render"))]
+fn ray_panel_intersection(
+    ray: &bevy_math::Ray3d,
+    transform: &bevy_transform::components::GlobalTransform,
+    aabb: &bevy_render::primitives::Aabb,
+) -> Option<(f32, bevy_math::Vec3, bevy_math::Vec3)> {
+    use bevy_math::Vec3;
+
+    let world_to_local = transform.affine().inverse();
+    let origin = world_to_local.transform_point3(ray.origin);
+    let direction = world_to_local.transform_vector3(*ray.direction);
+    let center = Vec3::from(aabb.center);
+    let half_extents = Vec3::from(aabb.half_extents);
+
+    let axis = (0..3)
+        .min_by(|&a, &b| half_extents[a].total_cmp(&half_extents[b]))
+        .unwrap_or(2);
+    if direction[axis].abs() <= f32::EPSILON {
+        return None;
+    }
+    let t = (center[axis] - origin[axis]) / direction[axis];
+    if t < 0.0 {
+        return None;
+    }
+    let local_hit = origin + direction * t;
+    let offset = (local_hit - center).abs();
+    for other_axis in (0..3).filter(|&i| i != axis) {
+        if offset[other_axis] > half_extents[other_axis] {
+            return None;
+        }
+    }
+
+    let position = transform.transform_point(local_hit);
+    let mut local_normal = Vec3::ZERO;
+    local_normal[axis] = -direction[axis].signum();
+    // Normals are transformed by the inverse transpose to account for non-uniform scale.
+    let normal = (world_to_local.matrix3.transpose() * bevy_math::Vec3A::from(local_normal))
+        .normalize_or_zero()
+        .into();
+    Some((position.distance(ray.origin), position, normal))
+}
+
 /// Updates textures painted by Egui.
+///
+/// All the deltas of a texture are first applied to its [`EguiManagedTexture::color_image`],
+/// and then the image asset gets updated only once per frame, regardless of the number of deltas.
 #[cfg(feature = "render")]
 pub fn update_egui_textures_system(
     mut egui_render_output: Query<
-        (Entity, &EguiRenderOutput),
-        Or<(With<Window>, With<EguiRenderToImage>)>,
+        (Entity, &EguiRenderOutput, &EguiContextSettings),
+        EguiContextsFilter,
     >,
     mut egui_managed_textures: ResMut<EguiManagedTextures>,
     mut image_assets: ResMut<Assets<Image>>,
+    mut dirty_textures: Local<bevy_utils::HashSet<u64>>,
+    mut texture_memory_stats: ResMut<EguiTextureMemoryStats>,
 ) {
-    for (entity, egui_render_output) in egui_render_output.iter_mut() {
-        for (texture_id, image_delta) in &egui_render_output.textures_delta.set {
-            let color_image = egui_node::as_color_image(&image_delta.image);
+    for (entity, egui_render_output, egui_settings) in egui_render_output.iter_mut() {
+        dirty_textures.clear();
 
+        for (texture_id, image_delta) in &egui_render_output.textures_delta.set {
             let texture_id = match texture_id {
                 egui::TextureId::Managed(texture_id) => *texture_id,
                 egui::TextureId::User(_) => continue,
             };
 
-            let sampler = ImageSampler::Descriptor(
-                egui_node::texture_options_as_sampler_descriptor(&image_delta.options),
-            );
-            if let Some(pos) = image_delta.pos {
+            let color_image = egui_node::as_color_image(&image_delta.image);
+            let sampler = egui_settings
+                .managed_texture_filter
+                .clone()
+                .unwrap_or_else(|| {
+                    ImageSampler::Descriptor(egui_node::texture_options_as_sampler_descriptor(
+                        &image_delta.options,
+                    ))
+                });
+            let managed_texture = egui_managed_textures.get_mut(&(entity, texture_id));
+            match (image_delta.pos, managed_texture) {
                 // Partial update.
-                if let Some(managed_texture) = egui_managed_textures.get_mut(&(entity, texture_id))
-                {
-                    // TODO: when bevy supports it, only update the part of the texture that changes.
+                (Some(pos), Some(managed_texture)) => {
                     update_image_rect(&mut managed_texture.color_image, pos, &color_image);
-                    let image =
-                        egui_node::color_image_as_bevy_image(&managed_texture.color_image, sampler);
-                    managed_texture.handle = image_assets.add(image);
-                } else {
+                    managed_texture.sampler = sampler;
+                    dirty_textures.insert(texture_id);
+                }
+                (Some(_), None) => {
                     log::warn!("Partial update of a missing texture (id: {:?})", texture_id);
                 }
-            } else {
-                // Full update.
-                let image = egui_node::color_image_as_bevy_image(&color_image, sampler);
-                let handle = image_assets.add(image);
-                egui_managed_textures.insert(
-                    (entity, texture_id),
-                    EguiManagedTexture {
-                        handle,
-                        color_image,
-                    },
-                );
+                // Full update of an existing texture, we reuse its asset handle.
+                (None, Some(managed_texture)) => {
+                    managed_texture.color_image = color_image;
+                    managed_texture.sampler = sampler;
+                    dirty_textures.insert(texture_id);
+                }
+                // New texture.
+                (None, None) => {
+                    let image = egui_node::color_image_as_bevy_image(&color_image, sampler.clone());
+                    let handle = image_assets.add(image);
+                    egui_managed_textures.insert(
+                        (entity, texture_id),
+                        EguiManagedTexture {
+                            handle,
+                            color_image,
+                            sampler,
+                        },
+                    );
+                }
+            }
+        }
+
+        for texture_id in dirty_textures.drain() {
+            let Some(managed_texture) = egui_managed_textures.get_mut(&(entity, texture_id)) else {
+                continue;
+            };
+            let image = egui_node::color_image_as_bevy_image(
+                &managed_texture.color_image,
+                managed_texture.sampler.clone(),
+            );
+            match image_assets.get_mut(&managed_texture.handle) {
+                Some(existing_image) => *existing_image = image,
+                None => managed_texture.handle = image_assets.add(image),
             }
         }
     }
 
+    texture_memory_stats.set_if_neq(EguiTextureMemoryStats::from_managed_textures(
+        &egui_managed_textures,
+    ));
+
     fn update_image_rect(dest: &mut egui::ColorImage, [x, y]: [usize; 2], src: &egui::ColorImage) {
+        let dest_width = dest.width();
         for sy in 0..src.height() {
-            for sx in 0..src.width() {
-                dest[(x + sx, y + sy)] = src[(sx, sy)];
-            }
+            let dest_start = (y + sy) * dest_width + x;
+            let src_start = sy * src.width();
+            dest.pixels[dest_start..dest_start + src.width()]
+                .copy_from_slice(&src.pixels[src_start..src_start + src.width()]);
         }
     }
 }
@@ -1277,17 +3024,31 @@ pub fn update_egui_textures_system(
 ///
 /// If you add textures via [`EguiContexts::add_image`] or [`EguiUserTextures::add_image`] by passing a weak handle,
 /// the systems ensures that corresponding Egui textures are cleaned up as well.
+/// Egui-managed textures of despawned contexts are deleted too.
 #[cfg(feature = "render")]
+#[allow(clippy::too_many_arguments)]
 pub fn free_egui_textures_system(
     mut egui_user_textures: ResMut<EguiUserTextures>,
-    egui_render_output: Query<
-        (Entity, &EguiRenderOutput),
-        Or<(With<Window>, With<EguiRenderToImage>)>,
-    >,
+    mut egui_context_user_textures: Query<&mut EguiContextUserTextures>,
+    egui_render_output: Query<(Entity, &EguiRenderOutput), EguiContextsFilter>,
     mut egui_managed_textures: ResMut<EguiManagedTextures>,
     mut image_assets: ResMut<Assets<Image>>,
     mut image_events: EventReader<AssetEvent<Image>>,
+    mut texture_memory_stats: ResMut<EguiTextureMemoryStats>,
+    mut removed_contexts: RemovedComponents<EguiContext>,
 ) {
+    // Textures of despawned contexts won't be freed by Egui.
+    let removed_contexts: bevy_utils::HashSet<Entity> = removed_contexts.read().collect();
+    if !removed_contexts.is_empty() {
+        egui_managed_textures.retain(|(entity, _), managed_texture| {
+            let keep = !removed_contexts.contains(entity);
+            if !keep {
+                image_assets.remove(&managed_texture.handle);
+            }
+            keep
+        });
+    }
+
     for (entity, egui_render_output) in egui_render_output.iter() {
         for &texture_id in &egui_render_output.textures_delta.free {
             if let egui::TextureId::Managed(texture_id) = texture_id {
@@ -1298,10 +3059,19 @@ pub fn free_egui_textures_system(
             }
         }
     }
+    texture_memory_stats.set_if_neq(EguiTextureMemoryStats::from_managed_textures(
+        &egui_managed_textures,
+    ));
 
     for image_event in image_events.read() {
         if let AssetEvent::Removed { id } = image_event {
-            egui_user_textures.remove_image(&Handle::<Image>::Weak(*id));
+            let handle = Handle::<Image>::Weak(*id);
+            egui_user_textures.remove_image(&handle);
+            for mut context_user_textures in egui_context_user_textures.iter_mut() {
+                if context_user_textures.image_id(&handle).is_some() {
+                    context_user_textures.remove_image(&handle);
+                }
+            }
         }
     }
 }
@@ -1371,17 +3141,48 @@ pub struct UpdateUiSizeAndScaleQuery {
     egui_input: &'static mut EguiInput,
     render_target_size: &'static mut RenderTargetSize,
     egui_settings: &'static EguiContextSettings,
+    pointer_position: &'static mut EguiContextPointerPosition,
+    throttle: &'static mut EguiPassThrottle,
     window: Option<&'static Window>,
     #[cfg(feature = "render")]
     render_to_image: Option<&'static EguiRenderToImage>,
+    #[cfg(feature = "render")]
+    render_to_texture_view: Option<&'static EguiRenderToTextureView>,
+    #[cfg(feature = "render")]
+    layer: Option<&'static EguiLayer>,
+    #[cfg(feature = "render")]
+    layer_rect: Option<&'static EguiLayerRect>,
 }
 
-/// Updates UI [`egui::RawInput::screen_rect`] and calls [`egui::Context::set_pixels_per_point`].
+/// Updates UI [`egui::RawInput::screen_rect`] and [`egui::RawInput::max_texture_side`], and calls [`egui::Context::set_pixels_per_point`].
+///
+/// Zero-sized render targets (such as minimized windows) keep their last valid [`RenderTargetSize`] and screen rect,
+/// so that Egui doesn't squash its windows and the UI is restored as is. Their passes are skipped until they get
+/// a valid size again (see [`EguiPassThrottle::skip_pass`]).
+///
+/// Scale factor changes (e.g. when a window is dragged to a monitor with a different DPI, which Bevy reports with
+/// [`bevy_window::WindowScaleFactorChanged`]) are applied within the same frame: the cached pointer position gets
+/// converted to the new scale and a repaint is requested, so that Egui re-rasterizes fonts for the new pixels per point.
 pub fn update_ui_size_and_scale_system(
     mut contexts: Query<UpdateUiSizeAndScaleQuery>,
     #[cfg(feature = "render")] images: Res<Assets<Image>>,
+    #[cfg(feature = "render")] windows: Query<&Window>,
+    #[cfg(feature = "render")] render_device: Option<Res<RenderDevice>>,
+    #[cfg(feature = "render")] manual_texture_views: Option<Res<ManualTextureViews>>,
 ) {
+    #[cfg(feature = "render")]
+    let device_max_texture_side =
+        render_device.map(|render_device| render_device.limits().max_texture_dimension_2d as usize);
+    #[cfg(not(feature = "render"))]
+    let device_max_texture_side = None;
+
     for mut context in contexts.iter_mut() {
+        context.throttle.skip_pass = false;
+        context.egui_input.max_texture_side = context
+            .egui_settings
+            .max_texture_side
+            .or(device_max_texture_side);
+
         let mut render_target_size = None;
         if let Some(window) = context.window {
             render_target_size = Some(RenderTargetSize::new(
@@ -1391,6 +3192,25 @@ pub fn update_ui_size_and_scale_system(
             ));
         }
         #[cfg(feature = "render")]
+        if let Some(EguiLayer { window, .. }) = context.layer {
+            if let Ok(window) = windows.get(*window) {
+                render_target_size = Some(match context.layer_rect {
+                    Some(EguiLayerRect(rect)) => RenderTargetSize::new(
+                        rect.width() * window.scale_factor(),
+                        rect.height() * window.scale_factor(),
+                        window.scale_factor(),
+                    ),
+                    None => RenderTargetSize::new(
+                        window.physical_width() as f32,
+                        window.physical_height() as f32,
+                        window.scale_factor(),
+                    ),
+                });
+            } else {
+                log::warn!("Invalid EguiLayer window: {window:?}");
+            }
+        }
+        #[cfg(feature = "render")]
         if let Some(EguiRenderToImage { handle, .. }) = context.render_to_image {
             if let Some(image) = images.get(handle) {
                 let size = image.size_f32();
@@ -1403,41 +3223,230 @@ pub fn update_ui_size_and_scale_system(
                 log::warn!("Invalid EguiRenderToImage handle: {handle:?}");
             }
         }
+        #[cfg(feature = "render")]
+        if let Some(EguiRenderToTextureView { handle, .. }) = context.render_to_texture_view {
+            if let Some(texture_view) = manual_texture_views
+                .as_deref()
+                .and_then(|views| views.get(handle))
+            {
+                render_target_size = Some(RenderTargetSize {
+                    physical_width: texture_view.size.x as f32,
+                    physical_height: texture_view.size.y as f32,
+                    scale_factor: 1.0,
+                })
+            } else {
+                log::warn!("Invalid EguiRenderToTextureView handle: {handle:?}");
+            }
+        }
 
+        // Headless contexts (without a render target) use the size that is set manually.
+        if render_target_size.is_none() && context.render_target_size.is_valid() {
+            render_target_size = Some(*context.render_target_size);
+        }
         let Some(new_render_target_size) = render_target_size else {
-            log::error!("bevy_egui context without window or render to texture!");
+            log::error!("bevy_egui context without window or render to texture (insert a valid RenderTargetSize for headless contexts)!");
             continue;
         };
-        let width = new_render_target_size.physical_width
-            / new_render_target_size.scale_factor
-            / context.egui_settings.scale_factor;
-        let height = new_render_target_size.physical_height
-            / new_render_target_size.scale_factor
-            / context.egui_settings.scale_factor;
-
-        if width < 1.0 || height < 1.0 {
+        if !apply_render_target_size(
+            context.ctx.get_mut(),
+            &mut context.egui_input,
+            new_render_target_size,
+            context.egui_settings,
+        ) {
+            context.throttle.skip_pass = true;
             continue;
         }
 
-        context.egui_input.screen_rect = Some(egui::Rect::from_min_max(
-            egui::pos2(0.0, 0.0),
-            egui::pos2(width, height),
+        let old_scale_factor = context.render_target_size.scale_factor;
+        let new_scale_factor = new_render_target_size.scale_factor;
+        if old_scale_factor > 0.0 && old_scale_factor != new_scale_factor {
+            // The pointer position is stored in points, keep it at the same physical position.
+            context.pointer_position.position = (context.pointer_position.position.to_vec2()
+                * (old_scale_factor / new_scale_factor))
+                .to_pos2();
+            context.ctx.get_mut().request_repaint();
+        }
+
+        *context.render_target_size = new_render_target_size;
+    }
+}
+
+/// Updates [`egui::RawInput::screen_rect`] and calls [`egui::Context::set_pixels_per_point`] according to the render target size
+/// and [`EguiContextSettings::scale_factor`], returns `false` (without updating anything) if the render target is too small.
+///
+/// The values are absolute, so applying the same size and settings again doesn't change anything.
+fn apply_render_target_size(
+    ctx: &mut egui::Context,
+    egui_input: &mut egui::RawInput,
+    render_target_size: RenderTargetSize,
+    egui_settings: &EguiContextSettings,
+) -> bool {
+    let width = render_target_size.physical_width
+        / render_target_size.scale_factor
+        / egui_settings.scale_factor;
+    let height = render_target_size.physical_height
+        / render_target_size.scale_factor
+        / egui_settings.scale_factor;
+
+    if width < 1.0 || height < 1.0 {
+        return false;
+    }
+
+    egui_input.screen_rect = Some(egui::Rect::from_min_max(
+        egui::pos2(0.0, 0.0),
+        egui::pos2(width, height),
+    ));
+    ctx.set_pixels_per_point(render_target_size.scale_factor * egui_settings.scale_factor);
+    true
+}
+
+/// Updates [`EguiLayerRect`] of the contexts bound to `bevy_ui` nodes with [`EguiUiNode`].
+///
+/// The rectangle is taken from the node layout computed during the previous frame, as `bevy_ui` computes it
+/// in [`PostUpdate`].
+#[cfg(feature = "bevy_ui")]
+pub fn update_ui_node_layer_rects_system(
+    mut contexts: Query<(&EguiUiNode, &mut EguiLayerRect)>,
+    nodes: Query<(
+        &bevy_ui::ComputedNode,
+        &bevy_transform::components::GlobalTransform,
+    )>,
+) {
+    for (ui_node, mut layer_rect) in contexts.iter_mut() {
+        let Ok((node, transform)) = nodes.get(ui_node.0) else {
+            continue;
+        };
+        layer_rect.set_if_neq(EguiLayerRect::from_ui_node(
+            node.size(),
+            transform.translation().truncate(),
+            node.inverse_scale_factor(),
         ));
+    }
+}
 
-        context.ctx.get_mut().set_pixels_per_point(
-            new_render_target_size.scale_factor * context.egui_settings.scale_factor,
-        );
+/// Applies [`EguiContextOptions`] to contexts whenever the component gets added or changed.
+///
+/// The zoom factor of the context is kept, as it's controlled by [`EguiContextSettings::scale_factor`].
+pub fn update_contexts_options_system(
+    mut contexts: Query<(&mut EguiContext, &EguiContextOptions), Changed<EguiContextOptions>>,
+) {
+    for (mut ctx, options) in contexts.iter_mut() {
+        ctx.get_mut().options_mut(|ctx_options| {
+            // The zoom factor is managed by `update_ui_size_and_scale_system`, which has already run.
+            let zoom_factor = ctx_options.zoom_factor;
+            *ctx_options = options.0.clone();
+            ctx_options.zoom_factor = zoom_factor;
+        });
+    }
+}
 
-        *context.render_target_size = new_render_target_size;
+/// Applies [`EguiContextSettings::max_double_click_delay`] to contexts whenever the settings or
+/// [`EguiContextOptions`] get changed.
+pub fn update_contexts_input_options_system(
+    mut contexts: Query<
+        (&mut EguiContext, &EguiContextSettings),
+        Or<(Changed<EguiContextSettings>, Changed<EguiContextOptions>)>,
+    >,
+) {
+    for (mut ctx, settings) in contexts.iter_mut() {
+        let Some(max_double_click_delay) = settings.max_double_click_delay else {
+            continue;
+        };
+        ctx.get_mut().options_mut(|options| {
+            options.input_options.max_double_click_delay = max_double_click_delay;
+        });
     }
 }
 
+/// Applies [`EguiContextStyle`] to contexts whenever the component gets added or changed, or whenever
+/// [`EguiContextOptions`] (which contain styles as well) get changed.
+pub fn update_contexts_style_system(
+    mut contexts: Query<
+        (&mut EguiContext, &EguiContextStyle),
+        Or<(Changed<EguiContextStyle>, Changed<EguiContextOptions>)>,
+    >,
+) {
+    for (mut ctx, style) in contexts.iter_mut() {
+        ctx.get_mut().set_style(style.0.clone());
+    }
+}
+
+/// Applies [`EguiFontMap`] to contexts once all of its fonts are loaded, re-applies it whenever the component
+/// gets changed or any of the fonts gets reloaded.
+#[cfg(feature = "render")]
+pub fn update_contexts_fonts_system(
+    mut contexts: Query<(Entity, &mut EguiContext, Option<Ref<EguiFontMap>>)>,
+    mut removed_font_maps: RemovedComponents<EguiFontMap>,
+    mut font_events: EventReader<AssetEvent<EguiFont>>,
+    fonts: Res<Assets<EguiFont>>,
+    mut pending: Local<bevy_utils::HashSet<Entity>>,
+) {
+    let reloaded_fonts: bevy_utils::HashSet<AssetId<EguiFont>> = font_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for entity in removed_font_maps.read() {
+        pending.remove(&entity);
+        if let Ok((_, mut ctx, None)) = contexts.get_mut(entity) {
+            ctx.get_mut().set_fonts(egui::FontDefinitions::default());
+        }
+    }
+
+    for (entity, mut ctx, font_map) in contexts.iter_mut() {
+        let Some(font_map) = font_map else {
+            continue;
+        };
+        if font_map.is_changed()
+            || font_map
+                .values()
+                .any(|handle| reloaded_fonts.contains(&handle.id()))
+        {
+            pending.insert(entity);
+        }
+        if !pending.contains(&entity) {
+            continue;
+        }
+        // The previous fonts are kept until all of the fonts are loaded.
+        let Some(definitions) = font_map.font_definitions(&fonts) else {
+            continue;
+        };
+        ctx.get_mut().set_fonts(definitions);
+        pending.remove(&entity);
+    }
+
+    pending.retain(|entity| contexts.contains(*entity));
+}
+
 /// Marks a pass start for Egui.
+///
+/// If [`EguiContextSettings`] have changed since the previous pass, the screen rect and pixels per point
+/// are recalculated first, so that the change applies to the pass that starts this frame.
 pub fn begin_pass_system(
-    mut contexts: Query<(&mut EguiContext, &EguiContextSettings, &mut EguiInput)>,
+    mut contexts: Query<(
+        &mut EguiContext,
+        Ref<EguiContextSettings>,
+        &mut EguiInput,
+        &RenderTargetSize,
+        &EguiPassThrottle,
+    )>,
 ) {
-    for (mut ctx, egui_settings, mut egui_input) in contexts.iter_mut() {
-        if !egui_settings.run_manually {
+    for (mut ctx, egui_settings, mut egui_input, render_target_size, throttle) in
+        contexts.iter_mut()
+    {
+        // Settings changed after `update_ui_size_and_scale_system` ran still apply to this pass.
+        if egui_settings.is_changed() && render_target_size.is_valid() {
+            apply_render_target_size(
+                ctx.get_mut(),
+                &mut egui_input,
+                *render_target_size,
+                &egui_settings,
+            );
+        }
+        if !egui_settings.run_manually && !throttle.skip_pass {
             ctx.get_mut().begin_pass(egui_input.take());
         }
     }
@@ -1445,19 +3454,615 @@ pub fn begin_pass_system(
 
 /// Marks a pass end for Egui.
 pub fn end_pass_system(
-    mut contexts: Query<(&mut EguiContext, &EguiContextSettings, &mut EguiFullOutput)>,
+    mut contexts: Query<(
+        Entity,
+        &mut EguiContext,
+        &EguiContextSettings,
+        &mut EguiFullOutput,
+        &EguiPassThrottle,
+    )>,
+    #[cfg(feature = "render")] layer_blend_states: Query<&EguiLayerBlendStates>,
 ) {
-    for (mut ctx, egui_settings, mut full_output) in contexts.iter_mut() {
-        if !egui_settings.run_manually {
+    for (_entity, mut ctx, egui_settings, mut full_output, throttle) in contexts.iter_mut() {
+        if !egui_settings.run_manually && !throttle.skip_pass {
+            #[cfg(feature = "render")]
+            if let Ok(layer_blend_states) = layer_blend_states.get(_entity) {
+                layer_blend_states.apply(ctx.get_mut());
+            }
             **full_output = Some(ctx.get_mut().end_pass());
         }
     }
 }
 
+/// Sets up GPU readback of [`EguiRenderToImage`] contexts according to [`EguiRenderToImage::readback`].
+#[cfg(feature = "render")]
+pub fn update_render_to_image_readback_system(
+    mut commands: Commands,
+    contexts: Query<
+        (
+            Entity,
+            &EguiRenderToImage,
+            Option<&bevy_render::gpu_readback::Readback>,
+        ),
+        Changed<EguiRenderToImage>,
+    >,
+) {
+    use bevy_render::gpu_readback::Readback;
+
+    for (entity, render_to_image, readback) in contexts.iter() {
+        match (render_to_image.readback, readback) {
+            (true, Some(Readback::Texture(handle))) if *handle == render_to_image.handle => {}
+            (true, _) => {
+                commands
+                    .entity(entity)
+                    .insert(Readback::texture(render_to_image.handle.clone()));
+            }
+            (false, Some(_)) => {
+                commands
+                    .entity(entity)
+                    .remove::<(Readback, EguiRenderToImageFrame)>();
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+/// Stores the pixels read back from [`EguiRenderToImage`] contexts into [`EguiRenderToImageFrame`].
+#[cfg(feature = "render")]
+pub fn update_render_to_image_frame_observer(
+    trigger: Trigger<bevy_render::gpu_readback::ReadbackComplete>,
+    mut commands: Commands,
+    mut contexts: Query<(&EguiRenderToImage, Option<&mut EguiRenderToImageFrame>)>,
+    images: Res<Assets<Image>>,
+) {
+    use bevy_image::TextureFormatPixelInfo;
+
+    let entity = trigger.entity();
+    let Ok((render_to_image, frame)) = contexts.get_mut(entity) else {
+        return;
+    };
+    if !render_to_image.readback {
+        return;
+    }
+    let Some(image) = images.get(&render_to_image.handle) else {
+        return;
+    };
+
+    let size = image.size();
+    if size.x == 0 || size.y == 0 {
+        return;
+    }
+    let row_size = size.x as usize * image.texture_descriptor.format.pixel_size();
+    // Rows of the readback buffer are padded to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+    let padded_row_size = RenderDevice::align_copy_bytes_per_row(row_size);
+    let readback_data = &trigger.event().0;
+    if readback_data.len() < padded_row_size * size.y as usize {
+        return;
+    }
+
+    let unpad_rows = |data: &mut Vec<u8>| {
+        data.clear();
+        for row in readback_data.chunks(padded_row_size).take(size.y as usize) {
+            data.extend_from_slice(&row[..row_size]);
+        }
+    };
+    match frame {
+        Some(mut frame) => {
+            frame.size = size;
+            unpad_rows(&mut frame.data);
+        }
+        None => {
+            let mut frame = EguiRenderToImageFrame {
+                size,
+                data: Vec::new(),
+            };
+            unpad_rows(&mut frame.data);
+            commands.entity(entity).insert(frame);
+        }
+    }
+}
+
+/// Decides whether contexts with [`EguiRenderToImage::update_interval`] skip their pass during the current frame.
+///
+/// Runs after [`update_ui_size_and_scale_system`], which has already marked the passes of too small render targets
+/// as skipped.
+#[cfg(feature = "render")]
+pub fn update_pass_throttle_system(
+    mut contexts: Query<(&EguiRenderToImage, &mut EguiPassThrottle)>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed();
+    for (render_to_image, mut throttle) in contexts.iter_mut() {
+        let skip_pass = throttle.skip_pass
+            || match (render_to_image.update_interval, throttle.last_pass) {
+                (Some(update_interval), Some(last_pass)) => now - last_pass < update_interval,
+                _ => false,
+            };
+        throttle.skip_pass = skip_pass;
+        if !skip_pass {
+            throttle.last_pass = Some(now);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use bevy::app::App;
+
+    /// Creates an app with [`crate::EguiPlugin`] that doesn't open any windows.
+    fn headless_app() -> App {
+        use bevy::{input::InputPlugin, prelude::*, window::ExitCondition};
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            },
+        ));
+        #[cfg(feature = "render")]
+        {
+            app.add_plugins(AssetPlugin::default());
+            app.init_asset::<bevy::render::render_resource::Shader>();
+            app.init_asset::<Image>();
+        }
+        app.add_plugins(crate::EguiPlugin);
+        app
+    }
+
     #[test]
     fn test_readme_deps() {
         version_sync::assert_markdown_deps_updated!("README.md");
     }
+
+    #[test]
+    fn test_input_events_keep_arrival_order() {
+        use crate::{EguiInput, EguiPreUpdateSet};
+        use bevy::{
+            input::{mouse::MouseButtonInput, ButtonState},
+            prelude::*,
+            window::{CursorMoved, WindowEvent},
+        };
+
+        #[derive(Resource, Default)]
+        struct ReceivedEvents(Vec<egui::Event>);
+
+        let mut app = headless_app();
+        app.init_resource::<ReceivedEvents>().add_systems(
+            PreUpdate,
+            (|inputs: Query<&EguiInput>, mut received: ResMut<ReceivedEvents>| {
+                for input in inputs.iter() {
+                    received.0.extend(input.events.iter().cloned());
+                }
+            })
+            .in_set(EguiPreUpdateSet::ModifyInput),
+        );
+        let window = app.world_mut().spawn(Window::default()).id();
+        app.update();
+        app.world_mut().resource_mut::<ReceivedEvents>().0.clear();
+
+        // `bevy_winit` sends every event both as a typed event and as `WindowEvent`.
+        let cursor_moved = |position| CursorMoved {
+            window,
+            position,
+            delta: None,
+        };
+        let pressed = MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window,
+        };
+        for event in [
+            WindowEvent::from(cursor_moved(Vec2::new(10.0, 20.0))),
+            WindowEvent::from(pressed),
+            WindowEvent::from(cursor_moved(Vec2::new(30.0, 40.0))),
+        ] {
+            match event.clone() {
+                WindowEvent::CursorMoved(event) => {
+                    app.world_mut().send_event(event);
+                }
+                WindowEvent::MouseButtonInput(event) => {
+                    app.world_mut().send_event(event);
+                }
+                _ => unreachable!(),
+            }
+            app.world_mut().send_event(event);
+        }
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<ReceivedEvents>().0,
+            vec![
+                egui::Event::PointerMoved(egui::pos2(10.0, 20.0)),
+                egui::Event::PointerButton {
+                    pos: egui::pos2(10.0, 20.0),
+                    button: egui::PointerButton::Primary,
+                    pressed: true,
+                    modifiers: egui::Modifiers::NONE,
+                },
+                egui::Event::PointerMoved(egui::pos2(30.0, 40.0)),
+            ]
+        );
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_layer_rect_takes_over_window_pointer_moves() {
+        use crate::{EguiInput, EguiLayer, EguiLayerRect, EguiPreUpdateSet};
+        use bevy::{
+            prelude::*,
+            window::{CursorMoved, WindowEvent},
+        };
+
+        #[derive(Resource, Default)]
+        struct ReceivedEvents(Vec<(Entity, egui::Event)>);
+
+        let mut app = headless_app();
+        app.init_resource::<ReceivedEvents>().add_systems(
+            PreUpdate,
+            (|inputs: Query<(Entity, &EguiInput)>, mut received: ResMut<ReceivedEvents>| {
+                for (entity, input) in inputs.iter() {
+                    received
+                        .0
+                        .extend(input.events.iter().map(|event| (entity, event.clone())));
+                }
+            })
+            .in_set(EguiPreUpdateSet::ModifyInput),
+        );
+        let window = app.world_mut().spawn(Window::default()).id();
+        let layer = app
+            .world_mut()
+            .spawn((
+                EguiLayer { window, order: 0 },
+                EguiLayerRect(Rect::new(0.0, 0.0, 50.0, 50.0)),
+            ))
+            .id();
+        app.update();
+
+        let move_cursor = |app: &mut App, position| {
+            app.world_mut().resource_mut::<ReceivedEvents>().0.clear();
+            let event = CursorMoved {
+                window,
+                position,
+                delta: None,
+            };
+            app.world_mut().send_event(event.clone());
+            app.world_mut().send_event(WindowEvent::from(event));
+            app.update();
+            std::mem::take(&mut app.world_mut().resource_mut::<ReceivedEvents>().0)
+        };
+
+        let events = move_cursor(&mut app, Vec2::new(10.0, 20.0));
+        assert!(events.contains(&(layer, egui::Event::PointerMoved(egui::pos2(10.0, 20.0)))));
+        assert!(events.contains(&(window, egui::Event::PointerGone)));
+        assert!(!events.iter().any(|(context, event)| {
+            *context == window && matches!(event, egui::Event::PointerMoved(_))
+        }));
+        let events = move_cursor(&mut app, Vec2::new(100.0, 200.0));
+        assert!(events.contains(&(window, egui::Event::PointerMoved(egui::pos2(100.0, 200.0)))));
+        assert!(events.contains(&(layer, egui::Event::PointerGone)));
+    }
+
+    #[test]
+    fn test_zero_sized_window_skips_passes() {
+        use crate::{EguiContext, EguiPassThrottle};
+        use bevy::{prelude::*, window::WindowResolution};
+
+        let mut app = headless_app();
+        let window = app.world_mut().spawn(Window::default()).id();
+        app.update();
+        let pass_nr = |app: &mut App| {
+            let mut entity = app.world_mut().entity_mut(window);
+            assert!(entity.get::<EguiPassThrottle>().is_some());
+            entity
+                .get_mut::<EguiContext>()
+                .unwrap()
+                .get_mut()
+                .cumulative_pass_nr()
+        };
+        let first_pass_nr = pass_nr(&mut app);
+
+        // Minimizing a window makes its size zero.
+        app.world_mut()
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution = WindowResolution::new(0.0, 0.0);
+        app.update();
+        app.update();
+        assert!(
+            app.world()
+                .get::<EguiPassThrottle>(window)
+                .unwrap()
+                .skip_pass
+        );
+        assert_eq!(pass_nr(&mut app), first_pass_nr);
+
+        app.world_mut()
+            .get_mut::<Window>(window)
+            .unwrap()
+            .resolution = WindowResolution::new(100.0, 100.0);
+        app.update();
+        assert!(
+            !app.world()
+                .get::<EguiPassThrottle>(window)
+                .unwrap()
+                .skip_pass
+        );
+        assert_eq!(pass_nr(&mut app), first_pass_nr + 1);
+    }
+
+    #[cfg(all(feature = "picking", feature = "render"))]
+    #[test]
+    fn test_ray_panel_intersection_depth() {
+        use bevy::{prelude::*, render::primitives::Aabb};
+
+        // A unit quad facing +Z, like `Plane3d::new(Vec3::Z, Vec2::splat(0.5))`.
+        let aabb = Aabb::from_min_max(Vec3::new(-0.5, -0.5, 0.0), Vec3::new(0.5, 0.5, 0.0));
+        let ray = Ray3d::new(Vec3::new(0.25, 0.0, 10.0), Dir3::NEG_Z);
+
+        let near = GlobalTransform::from_translation(Vec3::new(0.0, 0.0, 2.0));
+        let far =
+            GlobalTransform::from(Transform::from_xyz(0.0, 0.0, -3.0).with_scale(Vec3::splat(2.0)));
+        let (near_depth, near_position, near_normal) =
+            super::ray_panel_intersection(&ray, &near, &aabb).unwrap();
+        let (far_depth, _, _) = super::ray_panel_intersection(&ray, &far, &aabb).unwrap();
+        assert!((near_depth - 8.0).abs() < 1e-5);
+        assert!((far_depth - 13.0).abs() < 1e-5);
+        assert!(near_position.abs_diff_eq(Vec3::new(0.25, 0.0, 2.0), 1e-5));
+        assert!(near_normal.abs_diff_eq(Vec3::Z, 1e-5));
+
+        let beside = GlobalTransform::from_translation(Vec3::new(1.0, 0.0, 0.0));
+        assert!(super::ray_panel_intersection(&ray, &beside, &aabb).is_none());
+        let behind = GlobalTransform::from_translation(Vec3::new(0.0, 0.0, 11.0));
+        assert!(super::ray_panel_intersection(&ray, &behind, &aabb).is_none());
+    }
+
+    #[test]
+    fn test_copied_text_is_sent_as_copy_command() {
+        use crate::{EguiContext, EguiOutput, RenderTargetSize};
+        use bevy::prelude::*;
+
+        let mut app = headless_app();
+        app.add_systems(Update, |mut contexts: Query<&mut EguiContext>| {
+            for mut ctx in contexts.iter_mut() {
+                #[allow(deprecated)]
+                ctx.get_mut()
+                    .output_mut(|output| output.copied_text = "copied text".to_string());
+            }
+        });
+        let context = app
+            .world_mut()
+            .spawn((
+                EguiContext::default(),
+                RenderTargetSize::new(800.0, 600.0, 1.0),
+            ))
+            .id();
+
+        app.update();
+
+        let platform_output = &app
+            .world()
+            .get::<EguiOutput>(context)
+            .unwrap()
+            .platform_output;
+        assert_eq!(
+            platform_output.commands,
+            [egui::OutputCommand::CopyText("copied text".to_string())]
+        );
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_layer_blend_state_markers_survive_clipped_shapes() {
+        use crate::EguiLayerBlendStates;
+        use bevy::render::render_resource::BlendState;
+
+        let layer_id = egui::LayerId::new(egui::Order::Foreground, egui::Id::new("layer"));
+        let mut blend_states = EguiLayerBlendStates::default();
+        blend_states.insert(layer_id, BlendState::ALPHA_BLENDING);
+
+        let ctx = egui::Context::default();
+        let output = ctx.run(egui::RawInput::default(), |ctx| {
+            let painter = ctx.layer_painter(layer_id);
+            let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(10.0, 10.0));
+            // The first shape is clipped away entirely (e.g. by a collapsed scroll area).
+            painter
+                .with_clip_rect(egui::Rect::NOTHING)
+                .rect_filled(rect, 0.0, egui::Color32::RED);
+            painter.rect_filled(rect, 0.0, egui::Color32::GREEN);
+            blend_states.apply(ctx);
+        });
+
+        let callbacks = ctx
+            .tessellate(output.shapes, output.pixels_per_point)
+            .into_iter()
+            .filter(|primitive| matches!(primitive.primitive, egui::epaint::Primitive::Callback(_)))
+            .count();
+        assert_eq!(callbacks, 2);
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_despawned_render_to_image_context_removes_its_node() {
+        use crate::{render_systems::EguiPass, EguiPlugin, EguiRenderToImage};
+        use bevy::{
+            app::PluginsState,
+            input::InputPlugin,
+            prelude::*,
+            render::{render_graph::RenderGraph, RenderApp, RenderPlugin},
+            window::ExitCondition,
+        };
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            AssetPlugin::default(),
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            },
+            RenderPlugin {
+                synchronous_pipeline_compilation: true,
+                ..default()
+            },
+            ImagePlugin::default(),
+            EguiPlugin,
+        ));
+        while app.plugins_state() == PluginsState::Adding {
+            bevy::tasks::tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(Image::default());
+        let context = app.world_mut().spawn(EguiRenderToImage::new(image)).id();
+        app.update();
+        let has_node = |app: &mut App| {
+            app.sub_app_mut(RenderApp)
+                .world()
+                .resource::<RenderGraph>()
+                .get_node_state(EguiPass::from_render_to_image_entity(context))
+                .is_ok()
+        };
+        assert!(has_node(&mut app));
+
+        app.world_mut().despawn(context);
+        app.update();
+        assert!(!has_node(&mut app));
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_blend_states_specialize_separate_pipelines() {
+        use crate::egui_node::{EguiPipeline, EguiPipelineKey, EguiRenderTargetType};
+        use bevy::{
+            app::PluginsState,
+            prelude::*,
+            render::{
+                render_resource::{
+                    BlendState, PipelineCache, SpecializedRenderPipeline,
+                    SpecializedRenderPipelines, TextureFormat,
+                },
+                RenderApp, RenderPlugin,
+            },
+            window::ExitCondition,
+        };
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            },
+            RenderPlugin {
+                synchronous_pipeline_compilation: true,
+                ..default()
+            },
+            ImagePlugin::default(),
+        ));
+        while app.plugins_state() == PluginsState::Adding {
+            bevy::tasks::tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        let render_world = app.sub_app_mut(RenderApp).world_mut();
+        let egui_pipeline = EguiPipeline::from_world(render_world);
+        let mut pipelines = SpecializedRenderPipelines::<EguiPipeline>::default();
+        let pipeline_cache = render_world.resource::<PipelineCache>();
+        let key = |blend_state| EguiPipelineKey {
+            texture_format: TextureFormat::Rgba8UnormSrgb,
+            render_target_type: EguiRenderTargetType::Image,
+            blend_state,
+            dithering: true,
+        };
+
+        let premultiplied = pipelines.specialize(
+            pipeline_cache,
+            &egui_pipeline,
+            key(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+        );
+        let alpha = pipelines.specialize(
+            pipeline_cache,
+            &egui_pipeline,
+            key(BlendState::ALPHA_BLENDING),
+        );
+        assert_ne!(premultiplied, alpha);
+        for blend_state in [
+            BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendState::ALPHA_BLENDING,
+        ] {
+            let descriptor = egui_pipeline.specialize(key(blend_state));
+            let target = descriptor.fragment.unwrap().targets[0].clone().unwrap();
+            assert_eq!(target.blend, Some(blend_state));
+        }
+        assert_eq!(
+            pipelines.specialize(
+                pipeline_cache,
+                &egui_pipeline,
+                key(BlendState::ALPHA_BLENDING)
+            ),
+            alpha
+        );
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_dirty_rect_covers_changed_primitives() {
+        use crate::render_systems::dirty_rect;
+        use egui::{epaint::Primitive, pos2, ClippedPrimitive, Color32, Mesh, Rect};
+
+        let primitive = |rect: Rect, clip_rect: Rect| {
+            let mut mesh = Mesh::default();
+            mesh.add_colored_rect(rect, Color32::WHITE);
+            ClippedPrimitive {
+                clip_rect,
+                primitive: Primitive::Mesh(mesh),
+            }
+        };
+        let screen_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0));
+        let a = primitive(
+            Rect::from_min_max(pos2(0.0, 0.0), pos2(10.0, 10.0)),
+            screen_rect,
+        );
+        let b = primitive(
+            Rect::from_min_max(pos2(20.0, 20.0), pos2(30.0, 30.0)),
+            screen_rect,
+        );
+        let moved_b = primitive(
+            Rect::from_min_max(pos2(40.0, 40.0), pos2(50.0, 50.0)),
+            screen_rect,
+        );
+        let clipped_c = primitive(
+            Rect::from_min_max(pos2(60.0, 60.0), pos2(90.0, 90.0)),
+            Rect::from_min_max(pos2(60.0, 60.0), pos2(70.0, 70.0)),
+        );
+
+        let previous = [a.clone(), b.clone()];
+        assert!(!dirty_rect(&previous, &previous).is_positive());
+        assert_eq!(
+            dirty_rect(&previous, &[a.clone(), moved_b.clone()]),
+            Rect::from_min_max(pos2(20.0, 20.0), pos2(50.0, 50.0))
+        );
+        // Removed and added primitives are dirty as well, limited by their clip rects.
+        assert_eq!(
+            dirty_rect(&previous, std::slice::from_ref(&a)),
+            Rect::from_min_max(pos2(20.0, 20.0), pos2(30.0, 30.0))
+        );
+        assert_eq!(
+            dirty_rect(&previous, &[a, b, clipped_c]),
+            Rect::from_min_max(pos2(60.0, 60.0), pos2(70.0, 70.0))
+        );
+    }
 }
@@ -1,64 +1,160 @@
-use crate::{helpers, EguiContext, EguiContextSettings, EguiFullOutput, EguiRenderOutput};
+use crate::{
+    helpers, EguiContext, EguiContextSettings, EguiFullOutput, EguiNeedsRedraw, EguiOutput,
+    EguiPassThrottle, EguiRenderOutput, EguiRepaintRequested, EguiTessellationStats,
+    EguiWantsInput,
+};
 #[cfg(windows)]
 use bevy_ecs::system::Local;
 use bevy_ecs::{
+    change_detection::DetectChangesMut,
     entity::Entity,
     event::EventWriter,
+    query::Has,
     system::{NonSend, Query},
 };
-use bevy_window::RequestRedraw;
+use bevy_window::{RequestRedraw, Window, WindowCloseRequested};
 use bevy_winit::{cursor::CursorIcon, EventLoopProxy, WakeUp};
 use std::{sync::Arc, time::Duration};
 
 /// Reads Egui output.
+///
+/// [`egui::ViewportCommand::Close`] commands of window contexts are translated into [`WindowCloseRequested`] events
+/// (sent once per frame at most), so that they can be handled the same way as window close requests coming from the OS.
+///
+/// Sends [`EguiRepaintRequested`] events for contexts that requested a repaint.
+///
+/// Text set via the deprecated [`egui::PlatformOutput::copied_text`] is moved into an [`egui::OutputCommand::CopyText`]
+/// command, so it's handled (and exposed via [`EguiOutput`]) the same way as text copied with [`egui::Context::copy_text`].
 pub fn process_output_system(
     mut contexts: Query<(
         Entity,
         &mut EguiContext,
         &mut EguiFullOutput,
         &mut EguiRenderOutput,
+        &mut EguiOutput,
+        &mut EguiWantsInput,
+        &mut EguiTessellationStats,
+        &mut EguiNeedsRedraw,
         Option<&mut CursorIcon>,
         &EguiContextSettings,
+        &EguiPassThrottle,
+        Has<Window>,
     )>,
-    #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
-    mut egui_clipboard: bevy_ecs::system::ResMut<crate::EguiClipboard>,
+    #[cfg(feature = "manage_clipboard")] mut egui_clipboard: bevy_ecs::system::ResMut<
+        crate::EguiClipboard,
+    >,
     mut event: EventWriter<RequestRedraw>,
+    mut close_requested_writer: EventWriter<WindowCloseRequested>,
+    mut repaint_requested_writer: EventWriter<EguiRepaintRequested>,
     #[cfg(windows)] mut last_cursor_icon: Local<bevy_utils::HashMap<Entity, egui::CursorIcon>>,
     event_loop_proxy: Option<NonSend<EventLoopProxy<WakeUp>>>,
 ) {
     let mut should_request_redraw = false;
 
-    for (_entity, mut context, mut full_output, mut render_output, cursor_icon, _settings) in
-        contexts.iter_mut()
+    for (
+        entity,
+        mut context,
+        mut full_output,
+        mut render_output,
+        mut egui_output,
+        mut wants_input,
+        mut tessellation_stats,
+        mut needs_redraw,
+        cursor_icon,
+        _settings,
+        throttle,
+        is_window,
+    ) in contexts.iter_mut()
     {
+        if throttle.skip_pass {
+            // Paint jobs are kept to be rendered again, but textures delta must be applied only once.
+            if !render_output.textures_delta.is_empty() {
+                render_output.textures_delta = Default::default();
+            }
+            needs_redraw.set_if_neq(EguiNeedsRedraw(false));
+            continue;
+        }
+
         let ctx = context.get_mut();
         let Some(full_output) = full_output.0.take() else {
             bevy_log::error!("bevy_egui pass output has not been prepared (if EguiSettings::run_manually is set to true, make sure to call egui::Context::run or egui::Context::begin_pass and egui::Context::end_pass)");
             continue;
         };
         let egui::FullOutput {
-            platform_output,
+            mut platform_output,
             shapes,
             textures_delta,
             pixels_per_point,
-            viewport_output: _,
+            viewport_output,
         } = full_output;
+        let shapes_count = shapes.len();
         let paint_jobs = ctx.tessellate(shapes, pixels_per_point);
 
+        let mut stats = EguiTessellationStats {
+            shapes: shapes_count,
+            primitives: paint_jobs.len(),
+            ..Default::default()
+        };
+        for paint_job in &paint_jobs {
+            if let egui::epaint::Primitive::Mesh(mesh) = &paint_job.primitive {
+                stats.vertices += mesh.vertices.len();
+                stats.indices += mesh.indices.len();
+            }
+        }
+        tessellation_stats.set_if_neq(stats);
+
+        let close_requested = viewport_output.values().any(|viewport_output| {
+            viewport_output
+                .commands
+                .iter()
+                .any(|command| matches!(command, egui::ViewportCommand::Close))
+        });
+        if is_window && close_requested {
+            close_requested_writer.send(WindowCloseRequested { window: entity });
+        }
+
+        let repaint_delay = viewport_output
+            .values()
+            .map(|viewport_output| viewport_output.repaint_delay)
+            .min()
+            .unwrap_or(Duration::MAX);
+        if repaint_delay != Duration::MAX {
+            repaint_requested_writer.send(EguiRepaintRequested {
+                entity,
+                delay: repaint_delay,
+            });
+        }
+
         render_output.paint_jobs = Arc::new(paint_jobs);
         render_output.textures_delta = Arc::new(textures_delta);
+        render_output.pixels_per_point = pixels_per_point;
+
+        // Widgets that haven't migrated to `egui::OutputCommand::CopyText` yet still use this field.
+        #[allow(deprecated)]
+        let copied_text = std::mem::take(&mut platform_output.copied_text);
+        if !copied_text.is_empty() {
+            platform_output
+                .commands
+                .push(egui::OutputCommand::CopyText(copied_text));
+        }
+        egui_output.platform_output = platform_output.clone();
+
+        wants_input.set_if_neq(EguiWantsInput {
+            pointer: ctx.wants_pointer_input(),
+            keyboard: ctx.wants_keyboard_input(),
+        });
 
         for command in platform_output.commands {
             match command {
                 egui::OutputCommand::CopyText(_text) =>
                 {
-                    #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
+                    #[cfg(feature = "manage_clipboard")]
                     if !_text.is_empty() {
                         egui_clipboard.set_text(&_text);
                     }
                 }
                 egui::OutputCommand::CopyImage(_image) => {
-                    #[cfg(all(feature = "manage_clipboard", not(target_os = "android")))]
+                    #[cfg(feature = "manage_clipboard")]
                     egui_clipboard.set_image(&_image);
                 }
                 egui::OutputCommand::OpenUrl(_url) => {
@@ -95,7 +191,7 @@ pub fn process_output_system(
 
             #[cfg(windows)]
             {
-                let last_cursor_icon = last_cursor_icon.entry(_entity).or_default();
+                let last_cursor_icon = last_cursor_icon.entry(entity).or_default();
                 if *last_cursor_icon != platform_output.cursor_icon {
                     set_icon();
                     *last_cursor_icon = platform_output.cursor_icon;
@@ -105,8 +201,9 @@ pub fn process_output_system(
             set_icon();
         }
 
-        let needs_repaint = !render_output.is_empty();
-        should_request_redraw |= ctx.has_requested_repaint() && needs_repaint;
+        let needs_repaint = ctx.has_requested_repaint() && !render_output.is_empty();
+        needs_redraw.set_if_neq(EguiNeedsRedraw(needs_repaint));
+        should_request_redraw |= needs_repaint;
 
         // The resource doesn't exist in the headless mode.
         if let Some(event_loop_proxy) = &event_loop_proxy {
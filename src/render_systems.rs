@@ -1,24 +1,29 @@
 use crate::{
     egui_node::{
-        DrawCommand, DrawPrimitive, EguiBevyPaintCallback, EguiDraw, EguiNode, EguiPipeline,
-        EguiPipelineKey, EguiRenderTargetType, PaintCallbackDraw,
+        DrawCommand, DrawPrimitive, EguiBevyPaintCallback, EguiBlendStateMarker, EguiDraw,
+        EguiNode, EguiPipeline, EguiPipelineKey, EguiRenderTargetType, EguiRenderToImageBatch,
+        EguiRenderToImageBatchNode, PaintCallbackDraw,
     },
-    EguiContext, EguiContextSettings, EguiManagedTextures, EguiRenderOutput, EguiRenderToImage,
-    EguiUserTextures, RenderTargetSize,
+    EguiContext, EguiContextSettings, EguiContextUserTextures, EguiGlobalSettings, EguiLayer,
+    EguiLayerBlendStates, EguiManagedTextures, EguiPassThrottle, EguiRenderOutput,
+    EguiRenderToImage, EguiRenderToTextureView, EguiUserTextures, RenderTargetSize,
+    TextureColorConversion,
 };
 use bevy_asset::prelude::*;
 use bevy_derive::{Deref, DerefMut};
-use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_ecs::{prelude::*, query::QueryData, system::SystemParam};
 use bevy_image::Image;
 use bevy_log as log;
-use bevy_math::Vec2;
+use bevy_math::{Mat4, Vec2};
 use bevy_render::{
+    camera::{ManualTextureViewHandle, ManualTextureViews},
     extract_resource::ExtractResource,
     render_asset::RenderAssets,
     render_graph::{RenderGraph, RenderLabel},
     render_resource::{
-        BindGroup, BindGroupEntry, BindingResource, Buffer, BufferDescriptor, BufferId,
-        CachedRenderPipelineId, DynamicUniformBuffer, PipelineCache, SpecializedRenderPipelines,
+        BindGroup, BindGroupEntry, BindingResource, BlendState, Buffer, BufferDescriptor, BufferId,
+        CachedRenderPipelineId, DynamicUniformBuffer, LoadOp, PipelineCache, SamplerId,
+        SpecializedRenderPipelines, TextureViewId,
     },
     renderer::{RenderDevice, RenderQueue},
     sync_world::{MainEntity, RenderEntity},
@@ -26,9 +31,10 @@ use bevy_render::{
     view::ExtractedWindows,
     Extract,
 };
-use bevy_utils::HashMap;
+use bevy_utils::{HashMap, HashSet};
 use bevy_window::Window;
 use bytemuck::cast_slice;
+use std::sync::{atomic::AtomicBool, Arc};
 use wgpu_types::{BufferAddress, BufferUsages};
 
 /// Extracted Egui settings.
@@ -47,21 +53,26 @@ impl ExtractResource for ExtractedEguiManagedTextures {
 }
 
 /// Corresponds to Egui's [`egui::TextureId`].
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EguiTextureId {
     /// Textures allocated via Egui.
     Managed(MainEntity, u64),
     /// Textures allocated via Bevy.
     User(u64),
+    /// Textures allocated via Bevy for a specific context (see [`EguiContextUserTextures`]).
+    ContextUser(MainEntity, u64),
 }
 
 /// Extracted Egui textures.
 #[derive(SystemParam)]
-pub struct ExtractedEguiTextures<'w> {
+pub struct ExtractedEguiTextures<'w, 's> {
     /// Maps Egui managed texture ids to Bevy image handles.
     pub egui_textures: Res<'w, ExtractedEguiManagedTextures>,
     /// Maps Bevy managed texture handles to Egui user texture ids.
     pub user_textures: Res<'w, EguiUserTextures>,
+    /// Maps Bevy managed texture handles to Egui user texture ids of specific contexts.
+    pub context_user_textures:
+        Query<'w, 's, (&'static MainEntity, &'static EguiContextUserTextures)>,
 }
 
 /// [`RenderLabel`] type for the Egui pass.
@@ -75,6 +86,11 @@ pub struct EguiPass {
     pub render_target_type: EguiRenderTargetType,
 }
 
+/// [`RenderLabel`] type for the node drawing batched [`EguiRenderToImage`] contexts
+/// (see [`EguiGlobalSettings::batch_render_to_image_nodes`]).
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct EguiRenderToImageBatchPass;
+
 impl EguiPass {
     /// Creates a pass from a window Egui context.
     pub fn from_window_entity(entity: Entity) -> Self {
@@ -95,7 +111,21 @@ impl EguiPass {
     }
 }
 
-impl ExtractedEguiTextures<'_> {
+impl ExtractedEguiTextures<'_, '_> {
+    /// Returns the color conversion applied when sampling a texture (see [`TextureColorConversion`]).
+    pub fn color_conversion(&self, texture: EguiTextureId) -> TextureColorConversion {
+        match texture {
+            EguiTextureId::Managed(..) => TextureColorConversion::default(),
+            EguiTextureId::User(id) => self.user_textures.color_conversion(id),
+            EguiTextureId::ContextUser(main_entity, id) => self
+                .context_user_textures
+                .iter()
+                .find(|(entity, _)| **entity == main_entity)
+                .map(|(_, context_user_textures)| context_user_textures.0.color_conversion(id))
+                .unwrap_or_default(),
+        }
+    }
+
     /// Returns an iterator over all textures (both Egui and Bevy managed).
     pub fn handles(&self) -> impl Iterator<Item = (EguiTextureId, AssetId<Image>)> + '_ {
         self.egui_textures
@@ -113,38 +143,158 @@ impl ExtractedEguiTextures<'_> {
                     .iter()
                     .map(|(handle, id)| (EguiTextureId::User(*id), handle.id())),
             )
+            .chain(self.context_user_textures.iter().flat_map(
+                |(main_entity, context_user_textures)| {
+                    context_user_textures.0.textures.iter().map(|(handle, id)| {
+                        (EguiTextureId::ContextUser(*main_entity, *id), handle.id())
+                    })
+                },
+            ))
     }
 }
 
 /// Sets up render nodes for newly created Egui contexts.
+///
+/// Nodes of [`EguiLayer`] contexts are ordered after the node of their window context and
+/// between each other according to [`EguiLayer::order`]. Nodes of window contexts are ordered between each other
+/// according to [`EguiContextSettings::pass_order`], if it's set.
+///
+/// If [`EguiGlobalSettings::batch_render_to_image_nodes`] is enabled, [`EguiRenderToImage`] contexts get
+/// added to [`EguiRenderToImageBatch`] instead of getting their own nodes.
 pub fn setup_new_egui_nodes_system(
     windows: Extract<
-        Query<(Entity, &RenderEntity, AnyOf<(&Window, &EguiRenderToImage)>), Added<EguiContext>>,
+        Query<
+            (
+                Entity,
+                &RenderEntity,
+                AnyOf<(
+                    &Window,
+                    &EguiRenderToImage,
+                    &EguiRenderToTextureView,
+                    &EguiLayer,
+                )>,
+            ),
+            Added<EguiContext>,
+        >,
     >,
+    layers: Extract<Query<(Entity, &EguiLayer)>>,
+    window_settings: Extract<Query<&EguiContextSettings, (With<Window>, With<EguiContext>)>>,
+    egui_global_settings: Extract<Res<EguiGlobalSettings>>,
+    mut render_to_image_batch: ResMut<EguiRenderToImageBatch>,
     mut render_graph: ResMut<RenderGraph>,
+    mut pass_orders: Local<HashMap<Entity, i32>>,
 ) {
-    for (main_entity, render_entity, (window, render_to_image)) in windows.iter() {
+    for (main_entity, render_entity, (window, render_to_image, render_to_texture_view, layer)) in
+        windows.iter()
+    {
+        let render_target_type = match (window, render_to_image, render_to_texture_view, layer) {
+            (Some(_), None, None, None) | (None, None, None, Some(_)) => {
+                EguiRenderTargetType::Window
+            }
+            (None, Some(_), None, None) => EguiRenderTargetType::Image,
+            (None, None, Some(_), None) => EguiRenderTargetType::TextureView,
+            _ => {
+                log::error!(
+                    "Failed to set up an Egui node: can render only to either a window, an image, a texture view or a window layer"
+                );
+                continue;
+            }
+        };
+
+        if render_target_type == EguiRenderTargetType::Image {
+            if egui_global_settings.batch_render_to_image_nodes {
+                if render_graph
+                    .get_node::<EguiRenderToImageBatchNode>(EguiRenderToImageBatchPass)
+                    .is_err()
+                {
+                    render_graph.add_node(
+                        EguiRenderToImageBatchPass,
+                        EguiRenderToImageBatchNode::default(),
+                    );
+                    render_graph.add_node_edge(
+                        bevy_render::graph::CameraDriverLabel,
+                        EguiRenderToImageBatchPass,
+                    );
+                }
+                render_to_image_batch
+                    .0
+                    .insert(MainEntity::from(main_entity), *render_entity);
+                continue;
+            }
+
+            let egui_pass = EguiPass::from_render_to_image_entity(main_entity);
+            let new_node = EguiNode::new(
+                MainEntity::from(main_entity),
+                *render_entity,
+                render_target_type,
+            );
+            render_graph.add_node(egui_pass.clone(), new_node);
+            render_graph.add_node_edge(bevy_render::graph::CameraDriverLabel, egui_pass);
+            continue;
+        }
+
         let egui_pass = EguiPass::from_window_entity(main_entity);
         let new_node = EguiNode::new(
             MainEntity::from(main_entity),
             *render_entity,
-            match (window.is_some(), render_to_image.is_some()) {
-                (true, false) => EguiRenderTargetType::Window,
-                (false, true) => EguiRenderTargetType::Image,
-                (true, true) => {
-                    log::error!(
-                        "Failed to set up an Egui node: can't render both to a window and an image"
-                    );
-                    continue;
-                }
-                (false, false) => unreachable!(),
-            },
+            render_target_type,
         );
 
         render_graph.add_node(egui_pass.clone(), new_node);
 
         render_graph.add_node_edge(bevy_render::graph::CameraDriverLabel, egui_pass);
     }
+
+    for (main_entity, layer) in windows
+        .iter()
+        .filter_map(|(main_entity, _, (_, _, _, layer))| Some((main_entity, layer?)))
+    {
+        let egui_pass = EguiPass::from_window_entity(main_entity);
+        if let Err(err) = render_graph.try_add_node_edge(
+            EguiPass::from_window_entity(layer.window),
+            egui_pass.clone(),
+        ) {
+            log::error!("Failed to order an Egui layer node after its window node: {err:?}");
+        }
+
+        // Entities are compared as well to break ties between layers with the same order.
+        for (other_entity, other_layer) in layers.iter() {
+            if other_entity == main_entity || other_layer.window != layer.window {
+                continue;
+            }
+            let other_pass = EguiPass::from_window_entity(other_entity);
+            // An edge might already exist if both layers were added during the same frame.
+            let _ = if (other_layer.order, other_entity) < (layer.order, main_entity) {
+                render_graph.try_add_node_edge(other_pass, egui_pass.clone())
+            } else {
+                render_graph.try_add_node_edge(egui_pass.clone(), other_pass)
+            };
+        }
+    }
+
+    // Orders are compared with the ones in effect when the other nodes were created, so that
+    // changing the setting afterwards can't contradict the existing edges.
+    pass_orders.retain(|entity, _| {
+        render_graph
+            .get_node_state(EguiPass::from_window_entity(*entity))
+            .is_ok()
+    });
+    for (main_entity, order) in windows.iter().filter_map(|(main_entity, ..)| {
+        let settings = window_settings.get(main_entity).ok()?;
+        Some((main_entity, settings.pass_order?))
+    }) {
+        let egui_pass = EguiPass::from_window_entity(main_entity);
+        // Entities are compared as well to break ties between windows with the same order.
+        for (&other_entity, &other_order) in pass_orders.iter() {
+            let other_pass = EguiPass::from_window_entity(other_entity);
+            let _ = if (other_order, other_entity) < (order, main_entity) {
+                render_graph.try_add_node_edge(other_pass, egui_pass.clone())
+            } else {
+                render_graph.try_add_node_edge(egui_pass.clone(), other_pass)
+            };
+        }
+        pass_orders.insert(main_entity, order);
+    }
 }
 
 /// Tears render nodes down for deleted window Egui contexts.
@@ -160,11 +310,21 @@ pub fn teardown_window_nodes_system(
 }
 
 /// Tears render nodes down for deleted "render to texture" Egui contexts.
+///
+/// Batched contexts are removed from [`EguiRenderToImageBatch`], the batch node itself is kept.
 pub fn teardown_render_to_image_nodes_system(
     mut removed_windows: Extract<RemovedComponents<EguiRenderToImage>>,
+    mut render_to_image_batch: ResMut<EguiRenderToImageBatch>,
     mut render_graph: ResMut<RenderGraph>,
 ) {
     for window_entity in removed_windows.read() {
+        if render_to_image_batch
+            .0
+            .remove(&MainEntity::from(window_entity))
+            .is_some()
+        {
+            continue;
+        }
         if let Err(err) =
             render_graph.remove_node(EguiPass::from_render_to_image_entity(window_entity))
         {
@@ -173,6 +333,30 @@ pub fn teardown_render_to_image_nodes_system(
     }
 }
 
+/// Tears render nodes down for deleted [`EguiRenderToTextureView`] contexts.
+pub fn teardown_render_to_texture_view_nodes_system(
+    mut removed_texture_views: Extract<RemovedComponents<EguiRenderToTextureView>>,
+    mut render_graph: ResMut<RenderGraph>,
+) {
+    for entity in removed_texture_views.read() {
+        if let Err(err) = render_graph.remove_node(EguiPass::from_window_entity(entity)) {
+            log::error!("Failed to remove a render graph node: {err:?}");
+        }
+    }
+}
+
+/// Tears render nodes down for deleted [`EguiLayer`] contexts.
+pub fn teardown_layer_nodes_system(
+    mut removed_layers: Extract<RemovedComponents<EguiLayer>>,
+    mut render_graph: ResMut<RenderGraph>,
+) {
+    for layer_entity in removed_layers.read() {
+        if let Err(err) = render_graph.remove_node(EguiPass::from_window_entity(layer_entity)) {
+            log::error!("Failed to remove a render graph node: {err:?}");
+        }
+    }
+}
+
 /// Describes the transform buffer.
 #[derive(Resource, Default)]
 pub struct EguiTransforms {
@@ -192,28 +376,61 @@ pub struct EguiTransform {
     pub scale: Vec2,
     /// Normally equals `Vec2::new(-1.0, 1.0)`.
     pub translation: Vec2,
+    /// Color matrix applied in the fragment shader, see [`EguiContextSettings::color_tint`].
+    pub color_matrix: Mat4,
 }
 
 impl EguiTransform {
     /// Calculates the transform from window size and scale factor.
+    ///
+    /// Zero-sized render targets (such as minimized windows) get a zero scale instead of infinite or NaN values.
     pub fn from_render_target_size(
         render_target_size: RenderTargetSize,
         scale_factor: f32,
     ) -> Self {
+        let logical_size =
+            Vec2::new(render_target_size.width(), render_target_size.height()) / scale_factor;
+        if !render_target_size.is_valid() || !logical_size.cmpgt(Vec2::ZERO).all() {
+            return EguiTransform {
+                scale: Vec2::ZERO,
+                translation: Vec2::new(-1.0, 1.0),
+                color_matrix: Mat4::IDENTITY,
+            };
+        }
         EguiTransform {
-            scale: Vec2::new(
-                2.0 / (render_target_size.width() / scale_factor),
-                -2.0 / (render_target_size.height() / scale_factor),
-            ),
+            scale: Vec2::new(2.0 / logical_size.x, -2.0 / logical_size.y),
             translation: Vec2::new(-1.0, 1.0),
+            color_matrix: Mat4::IDENTITY,
         }
     }
 }
 
+/// Returns the scale factor that paint jobs were tessellated with (see [`EguiRenderOutput::pixels_per_point`]),
+/// falls back to [`EguiContextSettings::scale_factor`] if it's unknown.
+fn paint_jobs_scale_factor(
+    render_output: Option<&EguiRenderOutput>,
+    render_target_size: &RenderTargetSize,
+    egui_settings: &EguiContextSettings,
+) -> f32 {
+    match render_output {
+        Some(render_output)
+            if render_output.pixels_per_point > 0.0 && render_target_size.scale_factor > 0.0 =>
+        {
+            render_output.pixels_per_point / render_target_size.scale_factor
+        }
+        _ => egui_settings.scale_factor,
+    }
+}
+
 /// Prepares Egui transforms.
 pub fn prepare_egui_transforms_system(
     mut egui_transforms: ResMut<EguiTransforms>,
-    render_targets: Query<(Option<&MainEntity>, &EguiContextSettings, &RenderTargetSize)>,
+    render_targets: Query<(
+        Option<&MainEntity>,
+        &EguiContextSettings,
+        &RenderTargetSize,
+        Option<&EguiRenderOutput>,
+    )>,
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
     egui_pipeline: Res<EguiPipeline>,
@@ -221,13 +438,14 @@ pub fn prepare_egui_transforms_system(
     egui_transforms.buffer.clear();
     egui_transforms.offsets.clear();
 
-    for (window_main, egui_settings, size) in render_targets.iter() {
-        let offset = egui_transforms
-            .buffer
-            .push(&EguiTransform::from_render_target_size(
+    for (window_main, egui_settings, size, render_output) in render_targets.iter() {
+        let offset = egui_transforms.buffer.push(&EguiTransform {
+            color_matrix: egui_settings.color_tint.unwrap_or(Mat4::IDENTITY),
+            ..EguiTransform::from_render_target_size(
                 *size,
-                egui_settings.scale_factor,
-            ));
+                paint_jobs_scale_factor(render_output, size, egui_settings),
+            )
+        });
         if let Some(window_main) = window_main {
             egui_transforms.offsets.insert(*window_main, offset);
         }
@@ -260,76 +478,216 @@ pub fn prepare_egui_transforms_system(
 pub struct EguiTextureBindGroups(pub HashMap<EguiTextureId, BindGroup>);
 
 /// Queues bind groups.
+///
+/// Bind groups are cached across frames: an entry is recreated only if a texture is new or its [`GpuImage`]
+/// (texture view or sampler) has changed, entries of removed textures are dropped.
 pub fn queue_bind_groups_system(
-    mut commands: Commands,
+    mut egui_texture_bind_groups: ResMut<EguiTextureBindGroups>,
+    mut bind_group_sources: Local<
+        HashMap<EguiTextureId, (TextureViewId, SamplerId, TextureColorConversion)>,
+    >,
     egui_textures: ExtractedEguiTextures,
     render_device: Res<RenderDevice>,
     gpu_images: Res<RenderAssets<GpuImage>>,
     egui_pipeline: Res<EguiPipeline>,
 ) {
-    let bind_groups = egui_textures
-        .handles()
-        .filter_map(|(texture, handle_id)| {
-            let gpu_image = gpu_images.get(&Handle::Weak(handle_id))?;
-            let bind_group = render_device.create_bind_group(
-                None,
-                &egui_pipeline.texture_bind_group_layout,
-                &[
-                    BindGroupEntry {
-                        binding: 0,
-                        resource: BindingResource::TextureView(&gpu_image.texture_view),
-                    },
-                    BindGroupEntry {
-                        binding: 1,
-                        resource: BindingResource::Sampler(&gpu_image.sampler),
-                    },
-                ],
-            );
-            Some((texture, bind_group))
-        })
-        .collect();
+    let bind_groups = &mut egui_texture_bind_groups.0;
+    let mut alive_textures = HashSet::new();
+
+    for (texture, handle_id) in egui_textures.handles() {
+        let Some(gpu_image) = gpu_images.get(&Handle::Weak(handle_id)) else {
+            continue;
+        };
+        alive_textures.insert(texture);
+
+        let color_conversion = egui_textures.color_conversion(texture);
+        let source = (
+            gpu_image.texture_view.id(),
+            gpu_image.sampler.id(),
+            color_conversion,
+        );
+        if bind_group_sources.get(&texture) == Some(&source) && bind_groups.contains_key(&texture) {
+            continue;
+        }
+
+        let bind_group = render_device.create_bind_group(
+            None,
+            &egui_pipeline.texture_bind_group_layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&gpu_image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&gpu_image.sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: egui_pipeline
+                        .color_conversion_buffer(color_conversion)
+                        .as_entire_binding(),
+                },
+            ],
+        );
+        bind_groups.insert(texture, bind_group);
+        bind_group_sources.insert(texture, source);
+    }
+
+    bind_groups.retain(|texture, _| alive_textures.contains(texture));
+    bind_group_sources.retain(|texture, _| alive_textures.contains(texture));
+}
 
-    commands.insert_resource(EguiTextureBindGroups(bind_groups))
+/// Components that define a render target of an Egui context.
+#[derive(QueryData)]
+pub struct EguiRenderTargetQuery {
+    /// Main world entity of a context.
+    pub main_entity: &'static MainEntity,
+    /// Context settings.
+    pub settings: &'static EguiContextSettings,
+    /// Is set for contexts rendering to an image.
+    pub render_to_image: Option<&'static EguiRenderToImage>,
+    /// Is set for contexts rendering to a manual texture view.
+    pub render_to_texture_view: Option<&'static EguiRenderToTextureView>,
+    /// Is set for contexts rendering on top of a window.
+    pub layer: Option<&'static EguiLayer>,
+    /// Is set for contexts rendering some of their layers with custom blend states.
+    pub layer_blend_states: Option<&'static EguiLayerBlendStates>,
+}
+
+impl EguiRenderTargetQueryItem<'_> {
+    /// Constructs a pipeline key for the render target, returns [`None`] if the target isn't available.
+    pub fn pipeline_key(
+        &self,
+        windows: &ExtractedWindows,
+        images: &RenderAssets<GpuImage>,
+        manual_texture_views: &ManualTextureViews,
+    ) -> Option<EguiPipelineKey> {
+        if let Some(render_to_image) = self.render_to_image {
+            let image = images.get(&render_to_image.handle)?;
+            return Some(EguiPipelineKey::from_gpu_image(image, self.settings));
+        }
+        if let Some(render_to_texture_view) = self.render_to_texture_view {
+            let texture_view = manual_texture_views.get(&render_to_texture_view.handle)?;
+            return Some(EguiPipelineKey::from_manual_texture_view(
+                texture_view,
+                self.settings,
+            ));
+        }
+        let window_entity = self
+            .layer
+            .map_or(self.main_entity.id(), |layer| layer.window);
+        EguiPipelineKey::from_extracted_window(windows.get(&window_entity)?, self.settings)
+    }
 }
 
 /// Cached Pipeline IDs for the specialized instances of `EguiPipeline`.
-#[derive(Resource)]
+#[derive(Default, Resource)]
 pub struct EguiPipelines(pub HashMap<MainEntity, CachedRenderPipelineId>);
 
-/// Queue [`EguiPipeline`] instances specialized on each window's swap chain texture format.
+/// Cached Pipeline IDs for the instances of `EguiPipeline` specialized on [`EguiLayerBlendStates`].
+#[derive(Default, Resource)]
+pub struct EguiBlendStatePipelines(pub HashMap<(MainEntity, BlendState), CachedRenderPipelineId>);
+
+/// Queue [`EguiPipeline`] instances specialized on each render target's texture format and blend state.
+///
+/// A pipeline gets specialized only when a render target is new or its [`EguiPipelineKey`] has changed.
+/// Render targets with [`EguiLayerBlendStates`] also get a pipeline per each of the listed blend states.
+#[allow(clippy::too_many_arguments)]
 pub fn queue_pipelines_system(
-    mut commands: Commands,
+    mut egui_pipelines: ResMut<EguiPipelines>,
+    mut blend_state_pipelines: ResMut<EguiBlendStatePipelines>,
+    mut pipeline_keys: Local<HashMap<MainEntity, EguiPipelineKey>>,
     pipeline_cache: Res<PipelineCache>,
     mut specialized_pipelines: ResMut<SpecializedRenderPipelines<EguiPipeline>>,
     egui_pipeline: Res<EguiPipeline>,
     windows: Res<ExtractedWindows>,
-    render_to_image: Query<(&MainEntity, &EguiRenderToImage)>,
+    render_targets: Query<EguiRenderTargetQuery>,
     images: Res<RenderAssets<GpuImage>>,
+    manual_texture_views: Res<ManualTextureViews>,
 ) {
-    let mut pipelines: HashMap<MainEntity, CachedRenderPipelineId> = windows
-        .iter()
-        .filter_map(|(window_id, window)| {
-            let key = EguiPipelineKey::from_extracted_window(window)?;
-            let pipeline_id =
-                specialized_pipelines.specialize(&pipeline_cache, &egui_pipeline, key);
-            Some((MainEntity::from(*window_id), pipeline_id))
-        })
-        .collect();
-
-    pipelines.extend(
-        render_to_image
-            .iter()
-            .filter_map(|(main_entity, render_to_image)| {
-                let img = images.get(&render_to_image.handle)?;
-                let key = EguiPipelineKey::from_gpu_image(img);
-                let pipeline_id =
-                    specialized_pipelines.specialize(&pipeline_cache, &egui_pipeline, key);
+    let mut alive_blend_state_pipelines = HashSet::new();
+    for render_target in render_targets.iter() {
+        let Some(layer_blend_states) = render_target.layer_blend_states else {
+            continue;
+        };
+        let Some(key) = render_target.pipeline_key(&windows, &images, &manual_texture_views) else {
+            continue;
+        };
+        for blend_state in layer_blend_states.values() {
+            // Specialized pipelines are cached, so this is just a lookup for already queued keys.
+            let pipeline_id = specialized_pipelines.specialize(
+                &pipeline_cache,
+                &egui_pipeline,
+                EguiPipelineKey {
+                    blend_state: *blend_state,
+                    ..key
+                },
+            );
+            let pipeline_key = (*render_target.main_entity, *blend_state);
+            blend_state_pipelines.0.insert(pipeline_key, pipeline_id);
+            alive_blend_state_pipelines.insert(pipeline_key);
+        }
+    }
+    blend_state_pipelines
+        .0
+        .retain(|pipeline_key, _| alive_blend_state_pipelines.contains(pipeline_key));
 
-                Some((*main_entity, pipeline_id))
-            }),
-    );
+    let keys = render_targets.iter().filter_map(|render_target| {
+        let key = render_target.pipeline_key(&windows, &images, &manual_texture_views)?;
+        Some((*render_target.main_entity, key))
+    });
 
-    commands.insert_resource(EguiPipelines(pipelines));
+    let mut alive_targets = HashSet::new();
+    for (main_entity, key) in keys {
+        alive_targets.insert(main_entity);
+        if pipeline_keys.get(&main_entity) == Some(&key)
+            && egui_pipelines.0.contains_key(&main_entity)
+        {
+            continue;
+        }
+
+        let pipeline_id = specialized_pipelines.specialize(&pipeline_cache, &egui_pipeline, key);
+        egui_pipelines.0.insert(main_entity, pipeline_id);
+        pipeline_keys.insert(main_entity, key);
+    }
+
+    egui_pipelines
+        .0
+        .retain(|main_entity, _| alive_targets.contains(main_entity));
+    pipeline_keys.retain(|main_entity, _| alive_targets.contains(main_entity));
+}
+
+/// Warns about Egui meshes that reference textures without bind groups (for example, user textures that
+/// weren't added with [`crate::EguiContexts::add_image`] or were removed), as such meshes are skipped by [`EguiNode`].
+///
+/// A warning is logged once per context and texture, and repeated only if the texture goes missing again after
+/// it's been available.
+pub fn report_missing_textures_system(
+    render_data: Res<EguiRenderData>,
+    bind_groups: Res<EguiTextureBindGroups>,
+    mut reported_textures: Local<HashSet<(MainEntity, EguiTextureId)>>,
+) {
+    let mut missing_textures = HashSet::new();
+    for (main_entity, data) in render_data.0.iter() {
+        for draw_command in &data.draw_commands {
+            let DrawPrimitive::Egui(EguiDraw { egui_texture, .. }) = &draw_command.primitive else {
+                continue;
+            };
+            if bind_groups.contains_key(egui_texture) {
+                continue;
+            }
+            if missing_textures.insert((*main_entity, *egui_texture))
+                && !reported_textures.contains(&(*main_entity, *egui_texture))
+            {
+                log::warn!(
+                    "Egui texture {egui_texture:?} of the context {:?} has no bind group, its meshes won't be drawn",
+                    main_entity.id()
+                );
+            }
+        }
+    }
+    *reported_textures = missing_textures;
 }
 
 /// Cached Pipeline IDs for the specialized instances of `EguiPipeline`.
@@ -350,22 +708,124 @@ pub(crate) struct EguiRenderTargetData {
     pub(crate) pixels_per_point: f32,
     pub(crate) key: Option<EguiPipelineKey>,
     pub(crate) render_target_size: Option<RenderTargetSize>,
+    /// Overrides [`EguiRenderToImage::load_op`] for the current frame (see [`EguiRenderToImage::clear_once`]).
+    pub(crate) load_op_override: Option<LoadOp<wgpu_types::Color>>,
+    /// An image that has been cleared already.
+    pub(crate) cleared_image: Option<AssetId<Image>>,
+    /// An image that counts as cleared once the node records a pass for the current frame.
+    pending_cleared_image: Option<AssetId<Image>>,
+    /// Set by the node when it records a pass, which it may fail to do (e.g. if the pipeline isn't compiled yet).
+    pub(crate) pass_recorded: AtomicBool,
+    /// Region to redraw (in points) if [`EguiContextSettings::partial_redraw`] is enabled, [`None`] means full redraw.
+    pub(crate) dirty_rect: Option<egui::Rect>,
+    /// The state of the last frame the node has drawn, partial redraws are computed against it.
+    pub(crate) redraw_state: Option<EguiRedrawState>,
+    pub(crate) previous_paint_jobs: Arc<Vec<egui::ClippedPrimitive>>,
+    /// The state of the current frame, which replaces [`Self::redraw_state`] once the node records a pass.
+    pending_redraw_state: Option<(EguiRedrawState, Arc<Vec<egui::ClippedPrimitive>>)>,
+}
+
+/// The state of a render target that must stay the same for partial redraws to be possible.
+#[derive(PartialEq)]
+pub(crate) struct EguiRedrawState {
+    render_target_size: RenderTargetSize,
+    pixels_per_point: f32,
+    key: EguiPipelineKey,
+    image: Option<AssetId<Image>>,
+    texture_view: Option<ManualTextureViewHandle>,
+}
+
+/// Returns the union of the rectangles of primitives that differ between the two frames.
+///
+/// Primitives are compared by their index, so that inserting a primitive marks all the following ones as dirty.
+pub(crate) fn dirty_rect(
+    previous_paint_jobs: &[egui::ClippedPrimitive],
+    paint_jobs: &[egui::ClippedPrimitive],
+) -> egui::Rect {
+    fn primitive_rect(clipped_primitive: &egui::ClippedPrimitive) -> egui::Rect {
+        let rect = match &clipped_primitive.primitive {
+            egui::epaint::Primitive::Mesh(mesh) => mesh.calc_bounds(),
+            egui::epaint::Primitive::Callback(paint_callback) => paint_callback.rect,
+        };
+        rect.intersect(clipped_primitive.clip_rect)
+    }
+
+    fn is_same(a: &egui::ClippedPrimitive, b: &egui::ClippedPrimitive) -> bool {
+        // Paint callbacks are opaque to us, so they are always considered dirty.
+        a.clip_rect == b.clip_rect
+            && matches!(
+                (&a.primitive, &b.primitive),
+                (egui::epaint::Primitive::Mesh(a), egui::epaint::Primitive::Mesh(b)) if a == b
+            )
+    }
+
+    let mut dirty_rect = egui::Rect::NOTHING;
+    for i in 0..previous_paint_jobs.len().max(paint_jobs.len()) {
+        match (previous_paint_jobs.get(i), paint_jobs.get(i)) {
+            (Some(previous), Some(current)) if is_same(previous, current) => {}
+            (previous, current) => {
+                for clipped_primitive in previous.into_iter().chain(current) {
+                    dirty_rect = dirty_rect.union(primitive_rect(clipped_primitive));
+                }
+            }
+        }
+    }
+    dirty_rect
+}
+
+/// Callback transforming paint jobs of a render target before its vertex and index buffers get built.
+///
+/// The callback runs in the render world on the extracted [`EguiRenderOutput::paint_jobs`] (the paint jobs
+/// of the main world are left intact), which makes it possible to perturb the geometry or inject debug primitives.
+/// To register a callback, insert the resource into the render app:
+///
+/// ```no_run
+/// use bevy::{prelude::*, render::RenderApp};
+/// use bevy_egui::{egui, render_systems::EguiPaintJobsTransform, EguiPlugin};
+///
+/// let mut app = App::new();
+/// app.add_plugins((DefaultPlugins, EguiPlugin));
+/// app.sub_app_mut(RenderApp)
+///     .insert_resource(EguiPaintJobsTransform::new(|_render_target, paint_jobs| {
+///         for clipped_primitive in paint_jobs.iter_mut() {
+///             if let egui::epaint::Primitive::Mesh(mesh) = &mut clipped_primitive.primitive {
+///                 mesh.translate(egui::vec2(0.0, 1.0));
+///             }
+///         }
+///     }));
+/// ```
+#[derive(Resource)]
+pub struct EguiPaintJobsTransform(
+    pub Box<dyn Fn(MainEntity, &mut Vec<egui::ClippedPrimitive>) + Send + Sync>,
+);
+
+impl EguiPaintJobsTransform {
+    /// Creates the resource from a callback.
+    pub fn new(
+        transform: impl Fn(MainEntity, &mut Vec<egui::ClippedPrimitive>) + Send + Sync + 'static,
+    ) -> Self {
+        Self(Box::new(transform))
+    }
 }
 
 /// Prepares Egui transforms.
+///
+/// Paint jobs are passed through [`EguiPaintJobsTransform`] if the resource exists.
+#[allow(clippy::too_many_arguments)]
 pub fn prepare_egui_render_target_data(
     mut render_data: ResMut<EguiRenderData>,
     render_targets: Query<(
-        &MainEntity,
-        &EguiContextSettings,
+        EguiRenderTargetQuery,
         &RenderTargetSize,
         &EguiRenderOutput,
-        Option<&EguiRenderToImage>,
+        &EguiPassThrottle,
     )>,
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
     extracted_windows: Res<ExtractedWindows>,
     gpu_images: Res<RenderAssets<GpuImage>>,
+    manual_texture_views: Res<ManualTextureViews>,
+    paint_jobs_transform: Option<Res<EguiPaintJobsTransform>>,
 ) {
     let render_data = &mut render_data.0;
     render_data.retain(|_, data| {
@@ -374,58 +834,69 @@ pub fn prepare_egui_render_target_data(
         keep
     });
 
-    for (main_entity, egui_settings, render_target_size, render_output, render_to_image) in
-        render_targets.iter()
-    {
+    for (render_target, render_target_size, render_output, throttle) in render_targets.iter() {
+        let main_entity = render_target.main_entity;
         let data = render_data.entry(*main_entity).or_default();
 
         data.keep = true;
+        // The state prepared for the previous frame is applied only if the node has actually drawn it.
+        let pass_recorded = std::mem::take(data.pass_recorded.get_mut());
+        let pending_cleared_image = data.pending_cleared_image.take();
+        if pass_recorded && pending_cleared_image.is_some() {
+            data.cleared_image = pending_cleared_image;
+        }
+        match (data.pending_redraw_state.take(), pass_recorded) {
+            (Some((redraw_state, paint_jobs)), true) => {
+                data.redraw_state = Some(redraw_state);
+                data.previous_paint_jobs = paint_jobs;
+            }
+            // The node has drawn buffers that weren't prepared for the last frame.
+            (None, true) => data.redraw_state = None,
+            (_, false) => {}
+        }
+        // Updated textures may change the look of the primitives that haven't changed.
+        if !render_output.textures_delta.is_empty() {
+            data.redraw_state = None;
+        }
+        // Overrides are computed for the current frame only.
+        data.load_op_override = None;
+        data.dirty_rect = None;
 
-        let render_target_size = *render_target_size;
-        let egui_settings = egui_settings.clone();
-        let image_handle =
-            render_to_image.map(|render_to_image| render_to_image.handle.clone_weak());
+        // The node doesn't render skipped passes, the render target keeps the last rendered UI.
+        if throttle.skip_pass {
+            continue;
+        }
 
+        let render_target_size = *render_target_size;
         data.render_target_size = Some(render_target_size);
 
-        let render_target_type = if render_to_image.is_some() {
-            EguiRenderTargetType::Image
-        } else {
-            EguiRenderTargetType::Window
-        };
-
         // Construct a pipeline key based on a render target.
-        let key = match render_target_type {
-            EguiRenderTargetType::Window => {
-                let Some(key) = extracted_windows
-                    .windows
-                    .get(&main_entity.id())
-                    .and_then(EguiPipelineKey::from_extracted_window)
-                else {
-                    continue;
-                };
-                key
-            }
-            EguiRenderTargetType::Image => {
-                let image_handle = image_handle
-                    .expect("Expected an image handle for a render to image node")
-                    .clone();
-                let Some(key) = gpu_images
-                    .get(&image_handle)
-                    .map(EguiPipelineKey::from_gpu_image)
-                else {
-                    continue;
-                };
-                key
-            }
+        let Some(key) =
+            render_target.pipeline_key(&extracted_windows, &gpu_images, &manual_texture_views)
+        else {
+            continue;
         };
         data.key = Some(key);
 
-        data.pixels_per_point = render_target_size.scale_factor * egui_settings.scale_factor;
-        if render_target_size.physical_width == 0.0 || render_target_size.physical_height == 0.0 {
+        data.pixels_per_point = render_target_size.scale_factor
+            * paint_jobs_scale_factor(
+                Some(render_output),
+                &render_target_size,
+                render_target.settings,
+            );
+        if !render_target_size.is_valid() {
             continue;
         }
 
+        let paint_jobs = match &paint_jobs_transform {
+            Some(paint_jobs_transform) => {
+                let mut paint_jobs = render_output.paint_jobs.as_ref().clone();
+                (paint_jobs_transform.0)(*main_entity, &mut paint_jobs);
+                Arc::new(paint_jobs)
+            }
+            None => render_output.paint_jobs.clone(),
+        };
+
         let mut index_offset = 0;
 
         data.draw_commands.clear();
@@ -436,10 +907,23 @@ pub fn prepare_egui_render_target_data(
         for egui::epaint::ClippedPrimitive {
             clip_rect,
             primitive,
-        } in render_output.paint_jobs.as_slice()
+        } in paint_jobs.as_slice()
         {
             let clip_rect = *clip_rect;
 
+            // Blend state markers must not be culled along with the primitives.
+            if let egui::epaint::Primitive::Callback(paint_callback) = primitive {
+                if let Some(EguiBlendStateMarker(blend_state)) =
+                    paint_callback.callback.downcast_ref()
+                {
+                    data.draw_commands.push(DrawCommand {
+                        primitive: DrawPrimitive::SetBlendState(*blend_state),
+                        clip_rect,
+                    });
+                    continue;
+                }
+            }
+
             let clip_urect = bevy_math::URect {
                 min: bevy_math::UVec2 {
                     x: (clip_rect.min.x * data.pixels_per_point).round() as u32,
@@ -505,6 +989,9 @@ pub fn prepare_egui_render_target_data(
 
             let texture_handle = match mesh.texture_id {
                 egui::TextureId::Managed(id) => EguiTextureId::Managed(*main_entity, id),
+                egui::TextureId::User(id) if id >= EguiContextUserTextures::FIRST_ID => {
+                    EguiTextureId::ContextUser(*main_entity, id)
+                }
                 egui::TextureId::User(id) => EguiTextureId::User(id),
             };
 
@@ -547,5 +1034,34 @@ pub fn prepare_egui_render_target_data(
 
         render_queue.write_buffer(vertex_buffer, 0, &data.vertex_data);
         render_queue.write_buffer(index_buffer, 0, cast_slice(&data.index_data));
+
+        // The node is going to render this frame, so the image is cleared unless it's been cleared already.
+        if let Some(render_to_image) = render_target.render_to_image {
+            let image_id = render_to_image.handle.id();
+            data.load_op_override = (render_to_image.clear_once
+                && data.cleared_image == Some(image_id))
+            .then_some(LoadOp::Load);
+            data.pending_cleared_image = Some(image_id);
+        }
+
+        let redraw_state = EguiRedrawState {
+            render_target_size,
+            pixels_per_point: data.pixels_per_point,
+            key,
+            image: render_target
+                .render_to_image
+                .map(|render_to_image| render_to_image.handle.id()),
+            texture_view: render_target
+                .render_to_texture_view
+                .map(|render_to_texture_view| render_to_texture_view.handle),
+        };
+        if render_target.settings.partial_redraw
+            && (redraw_state.image.is_some() || redraw_state.texture_view.is_some())
+            && data.redraw_state.as_ref() == Some(&redraw_state)
+        {
+            data.dirty_rect = Some(dirty_rect(&data.previous_paint_jobs, &paint_jobs));
+            data.load_op_override = Some(LoadOp::Load);
+        }
+        data.pending_redraw_state = Some((redraw_state, paint_jobs));
     }
 }
@@ -2,7 +2,7 @@
 //! mobile keyboard and IME input.
 
 use crate::{
-    input::{EguiInputEvent, FocusedNonWindowEguiContext},
+    input::{EguiInputEvent, EguiInputTimeline, FocusedNonWindowEguiContext},
     EguiContext, EguiContextSettings, EguiInput, EguiOutput, EventClosure, SubscribedEvents,
 };
 use bevy_ecs::prelude::*;
@@ -77,13 +77,14 @@ pub fn process_safari_virtual_keyboard_system(
     };
 }
 
-/// Listens to the [`TextAgentChannel`] channel and wraps messages into [`EguiInputEvent`] events.
+/// Listens to the [`TextAgentChannel`] channel and wraps messages into [`EguiInputEvent`] events
+/// (ordered after the window events in [`EguiInputTimeline`]).
 pub fn write_text_agent_channel_events_system(
     channel: Res<TextAgentChannel>,
     focused_non_window_egui_context: Option<Res<FocusedNonWindowEguiContext>>,
     // We can safely assume that we have only 1 window in WASM.
     egui_context: Single<(Entity, &EguiContextSettings), (With<PrimaryWindow>, With<EguiContext>)>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
     mut redraw_event: EventWriter<RequestRedraw>,
 ) {
     let (primary_context, context_settings) = *egui_context;
@@ -98,9 +99,10 @@ pub fn write_text_agent_channel_events_system(
     let context = focused_non_window_egui_context
         .as_deref()
         .map_or(primary_context, |context| context.0);
+    let arrival = timeline.frame_end();
     while let Ok(event) = channel.receiver.try_recv() {
         redraw = true;
-        egui_input_event_writer.send(EguiInputEvent { context, event });
+        timeline.send(arrival, EguiInputEvent { context, event });
     }
     if redraw {
         redraw_event.send(RequestRedraw);
@@ -0,0 +1,87 @@
+//! Shows the on-screen keyboard of the OS when Egui expects text input, which is useful for touchscreen
+//! kiosks and tablets without a physical keyboard.
+//!
+//! Only Windows is supported for now (the Touch Keyboard, `TabTip.exe`), the system is a no-op on other platforms.
+
+use crate::{EguiInput, EguiOutput};
+use bevy_ecs::prelude::*;
+
+/// Shows the on-screen keyboard when a text field of a focused context gets focused (i.e. Egui reports
+/// [`egui::PlatformOutput::ime`]) and hides it once no text field is focused.
+pub fn update_virtual_keyboard_system(
+    egui_contexts: Query<(&EguiInput, &EguiOutput)>,
+    mut is_shown: Local<bool>,
+) {
+    let wants_text_input = egui_contexts.iter().any(|(egui_input, egui_output)| {
+        egui_input.focused && egui_output.platform_output.ime.is_some()
+    });
+    if wants_text_input == *is_shown {
+        return;
+    }
+
+    *is_shown = wants_text_input;
+    if wants_text_input {
+        platform::show();
+    } else {
+        platform::hide();
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use bevy_log as log;
+    use std::{
+        ffi::{c_void, OsStr},
+        os::windows::ffi::OsStrExt,
+        path::PathBuf,
+        process::Command,
+    };
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn FindWindowW(class_name: *const u16, window_name: *const u16) -> *mut c_void;
+        fn PostMessageW(hwnd: *mut c_void, msg: u32, wparam: usize, lparam: isize) -> i32;
+    }
+
+    const WM_SYSCOMMAND: u32 = 0x0112;
+    const SC_CLOSE: usize = 0xF060;
+
+    pub fn show() {
+        // `CommonProgramW6432` points to the 64-bit directory even for 32-bit processes.
+        let common_files = std::env::var_os("CommonProgramW6432")
+            .or_else(|| std::env::var_os("CommonProgramFiles"))
+            .map_or_else(
+                || PathBuf::from(r"C:\Program Files\Common Files"),
+                PathBuf::from,
+            );
+        let tab_tip = common_files.join(r"microsoft shared\ink\TabTip.exe");
+        if let Err(err) = Command::new(&tab_tip).spawn() {
+            log::error!(
+                "Failed to show the touch keyboard ({}): {:?}",
+                tab_tip.display(),
+                err
+            );
+        }
+    }
+
+    pub fn hide() {
+        let class_name: Vec<u16> = OsStr::new("IPTip_Main_Window")
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+        // SAFETY: the class name is a valid null-terminated wide string, the window handle is checked for null.
+        unsafe {
+            let hwnd = FindWindowW(class_name.as_ptr(), std::ptr::null());
+            if !hwnd.is_null() {
+                PostMessageW(hwnd, WM_SYSCOMMAND, SC_CLOSE, 0);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    pub fn show() {}
+
+    pub fn hide() {}
+}
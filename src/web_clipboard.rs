@@ -1,5 +1,5 @@
 use crate::{
-    input::{EguiInputEvent, FocusedNonWindowEguiContext},
+    input::{EguiInputEvent, EguiInputTimeline, FocusedNonWindowEguiContext},
     string_from_js_value, EguiClipboard, EguiContext, EguiContextSettings, EventClosure,
     SubscribedEvents,
 };
@@ -22,13 +22,14 @@ pub fn startup_setup_web_events_system(
     setup_clipboard_paste(&mut subscribed_events, tx);
 }
 
-/// Receives web clipboard events and wraps them as [`EguiInputEvent`] events.
+/// Receives web clipboard events and wraps them as [`EguiInputEvent`] events
+/// (ordered after the window events in [`EguiInputTimeline`]).
 pub fn write_web_clipboard_events_system(
     focused_non_window_egui_context: Option<Res<FocusedNonWindowEguiContext>>,
     // We can safely assume that we have only 1 window in WASM.
     egui_context: Single<(Entity, &EguiContextSettings), (With<PrimaryWindow>, With<EguiContext>)>,
     mut egui_clipboard: ResMut<EguiClipboard>,
-    mut egui_input_event_writer: EventWriter<EguiInputEvent>,
+    mut timeline: ResMut<EguiInputTimeline>,
 ) {
     let (primary_context, context_settings) = *egui_context;
     if !context_settings
@@ -41,26 +42,36 @@ pub fn write_web_clipboard_events_system(
     let context = focused_non_window_egui_context
         .as_deref()
         .map_or(primary_context, |context| context.0);
+    let arrival = timeline.frame_end();
     while let Some(event) = egui_clipboard.try_receive_clipboard_event() {
         match event {
             crate::web_clipboard::WebClipboardEvent::Copy => {
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::Copy,
-                });
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::Copy,
+                    },
+                );
             }
             crate::web_clipboard::WebClipboardEvent::Cut => {
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::Cut,
-                });
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::Cut,
+                    },
+                );
             }
             crate::web_clipboard::WebClipboardEvent::Paste(text) => {
                 egui_clipboard.set_text_internal(&text);
-                egui_input_event_writer.send(EguiInputEvent {
-                    context,
-                    event: egui::Event::Paste(text),
-                });
+                timeline.send(
+                    arrival,
+                    EguiInputEvent {
+                        context,
+                        event: egui::Event::Paste(text),
+                    },
+                );
             }
         }
     }